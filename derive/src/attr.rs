@@ -1,46 +1,554 @@
-use syn::{Attribute, Error, Field, Lit, Meta, NestedMeta, Result, Variant};
+use crate::case::RenameRule;
+use syn::{Attribute, Error, ExprPath, Lit, LitStr, Meta, NestedMeta, Result, Type};
 
-/// Find the value of a #[serde(rename = "...")] attribute.
-fn attr_rename(attrs: &[Attribute]) -> Result<Option<String>> {
+/// Attributes on the struct or enum being derived.
+pub struct Container {
+    /// #[serde(default)] or #[serde(default = "...")]
+    pub default: Option<Default>,
+    /// #[serde(rename_all = "...")]
+    pub rename_all: Option<RenameRule>,
+    /// #[serde(deny_unknown_fields)]
+    pub deny_unknown_fields: bool,
+    /// #[serde(transparent)]
+    pub transparent: bool,
+    /// #[serde(tag = "...")], #[serde(content = "...")], #[serde(untagged)],
+    /// #[serde(repr = "int")]
+    pub tagging: Tagging,
+}
+
+/// Container attributes that route (de)serialization through another type.
+pub struct Conversions {
+    /// #[serde(from = "...")] or #[serde(try_from = "...")]
+    pub from: Option<From>,
+    /// #[serde(into = "...")]
+    pub into: Option<Type>,
+}
+
+/// How to build the container out of the type it is deserialized as.
+pub enum From {
+    /// Using the container's `From` impl.
+    Infallible(Type),
+    /// Using the container's `TryFrom` impl, failing on error.
+    Fallible(Type),
+}
+
+/// How the variants of an enum are represented.
+pub enum Tagging {
+    /// `{"Variant": ...}`
+    External,
+    /// `{"tag": "Variant", ...}`
+    Internal { tag: String },
+    /// `{"tag": "Variant", "content": ...}`
+    Adjacent { tag: String, content: String },
+    /// The variant's data alone.
+    Untagged,
+    /// The discriminant of a unit-only enum as a number.
+    Int,
+}
+
+/// Attributes on a named field.
+pub struct Field {
+    /// Serialized name, respecting a rename attribute.
+    pub name: String,
+    /// Additional names accepted during deserialization.
+    pub aliases: Vec<String>,
+    /// #[serde(default)] or #[serde(default = "...")]
+    pub default: Option<Default>,
+    /// #[serde(skip)] or #[serde(skip_serializing)]
+    pub skip_serializing: bool,
+    /// #[serde(skip)] or #[serde(skip_deserializing)]
+    pub skip_deserializing: bool,
+    /// #[serde(skip_serializing_if = "...")]
+    pub skip_serializing_if: Option<ExprPath>,
+    /// #[serde(flatten)]
+    pub flatten: bool,
+    /// #[serde(serialize_with = "...")] or #[serde(with = "...")]
+    pub serialize_with: Option<ExprPath>,
+    /// #[serde(deserialize_with = "...")] or #[serde(with = "...")]
+    pub deserialize_with: Option<ExprPath>,
+}
+
+/// Attributes on an enum variant.
+pub struct Variant {
+    /// Serialized name, respecting a rename attribute.
+    pub name: String,
+    /// Additional names accepted during deserialization.
+    pub aliases: Vec<String>,
+    /// #[serde(other)]
+    pub other: bool,
+}
+
+/// Where to get a value for a field that is missing from the input.
+pub enum Default {
+    /// Use the type's Default impl.
+    Trait,
+    /// Call the given function.
+    Path(ExprPath),
+}
+
+impl Container {
+    /// Attributes in effect for the fields of an enum's struct variants.
+    pub fn variant_fields(&self) -> Container {
+        Container {
+            default: None,
+            rename_all: None,
+            deny_unknown_fields: self.deny_unknown_fields,
+            transparent: false,
+            tagging: Tagging::External,
+        }
+    }
+}
+
+pub fn container(attrs: &[Attribute]) -> Result<Container> {
+    let mut default = None;
+    let mut rename_all = None;
+    let mut deny_unknown_fields = None;
+    let mut transparent = None;
+    let mut tag = None;
+    let mut content = None;
+    let mut untagged = None;
+    let mut repr = None;
+
+    for meta in serde_meta(attrs)? {
+        if is_conversion(&meta) {
+            continue;
+        }
+        if let Some(value) = parse_default(&meta)? {
+            set_once(&mut default, value, &meta, "default")?;
+            continue;
+        }
+        if let Some(value) = parse_string(&meta, "rename_all") {
+            let rule = match RenameRule::from_str(&value.value()) {
+                Some(rule) => rule,
+                None => return Err(Error::new_spanned(value, "unknown rename rule")),
+            };
+            set_once(&mut rename_all, rule, &meta, "rename_all")?;
+            continue;
+        }
+        if parse_flag(&meta, "deny_unknown_fields") {
+            set_once(&mut deny_unknown_fields, (), &meta, "deny_unknown_fields")?;
+            continue;
+        }
+        if parse_flag(&meta, "transparent") {
+            set_once(&mut transparent, meta.clone(), &meta, "transparent")?;
+            continue;
+        }
+        if let Some(value) = parse_string(&meta, "tag") {
+            set_once(&mut tag, value.value(), &meta, "tag")?;
+            continue;
+        }
+        if let Some(value) = parse_string(&meta, "content") {
+            set_once(
+                &mut content,
+                (value.value(), meta.clone()),
+                &meta,
+                "content",
+            )?;
+            continue;
+        }
+        if parse_flag(&meta, "untagged") {
+            set_once(&mut untagged, meta.clone(), &meta, "untagged")?;
+            continue;
+        }
+        if let Some(value) = parse_string(&meta, "repr") {
+            if value.value() != "int" {
+                return Err(Error::new_spanned(value, "expected \"int\""));
+            }
+            set_once(&mut repr, meta.clone(), &meta, "repr")?;
+            continue;
+        }
+        return Err(Error::new_spanned(meta, "unsupported attribute"));
+    }
+
+    if let Some(meta) = &transparent {
+        if default.is_some()
+            || rename_all.is_some()
+            || deny_unknown_fields.is_some()
+            || tag.is_some()
+            || content.is_some()
+            || untagged.is_some()
+            || repr.is_some()
+        {
+            return Err(Error::new_spanned(
+                meta,
+                "#[serde(transparent)] cannot be combined with other container attributes",
+            ));
+        }
+    }
+
+    if let Some(meta) = &repr {
+        if tag.is_some() || untagged.is_some() {
+            return Err(Error::new_spanned(
+                meta,
+                "#[serde(repr = \"int\")] cannot be combined with a tag or untagged",
+            ));
+        }
+    }
+
+    let tagging = match (tag, content, untagged) {
+        (None, None, None) if repr.is_some() => Tagging::Int,
+        (None, None, None) => Tagging::External,
+        (Some(tag), None, None) => Tagging::Internal { tag },
+        (Some(tag), Some((content, _)), None) => Tagging::Adjacent { tag, content },
+        (None, None, Some(_)) => Tagging::Untagged,
+        (None, Some((_, meta)), _) => {
+            return Err(Error::new_spanned(
+                meta,
+                "#[serde(content = \"...\")] requires #[serde(tag = \"...\")]",
+            ));
+        }
+        (Some(_), _, Some(meta)) => {
+            return Err(Error::new_spanned(
+                meta,
+                "#[serde(untagged)] cannot be combined with a tag",
+            ));
+        }
+    };
+
+    Ok(Container {
+        default,
+        rename_all,
+        deny_unknown_fields: deny_unknown_fields.is_some(),
+        transparent: transparent.is_some(),
+        tagging,
+    })
+}
+
+pub fn conversions(attrs: &[Attribute]) -> Result<Conversions> {
+    let mut from = None;
+    let mut into = None;
+
+    for meta in serde_meta(attrs)? {
+        let value = if let Some(ty) = parse_string(&meta, "from") {
+            Some(From::Infallible(ty.parse()?))
+        } else if let Some(ty) = parse_string(&meta, "try_from") {
+            Some(From::Fallible(ty.parse()?))
+        } else {
+            None
+        };
+        if let Some(value) = value {
+            if from.is_some() {
+                return Err(Error::new_spanned(
+                    meta,
+                    "only one of #[serde(from)] and #[serde(try_from)] is allowed",
+                ));
+            }
+            from = Some(value);
+        } else if let Some(ty) = parse_string(&meta, "into") {
+            set_once(&mut into, ty.parse()?, &meta, "into")?;
+        }
+    }
+
+    Ok(Conversions { from, into })
+}
+
+/// Check the container attributes that come with #[serde(from)] or
+/// #[serde(try_from)], which the derived Deserialize impl does not look at.
+/// Those that matter only to deserialization have no effect at all, and with
+/// #[serde(into)] neither do any of the rest.
+pub fn check_from(attrs: &[Attribute], conversions: &Conversions) -> Result<()> {
+    container(attrs)?;
+
+    for meta in serde_meta(attrs)? {
+        if is_conversion(&meta) {
+            continue;
+        }
+        if parse_default(&meta)?.is_some() || parse_flag(&meta, "deny_unknown_fields") {
+            return Err(Error::new_spanned(
+                meta,
+                "this attribute has no effect with #[serde(from)] or #[serde(try_from)]",
+            ));
+        }
+        if conversions.into.is_some() {
+            return Err(Error::new_spanned(
+                meta,
+                "this attribute has no effect with #[serde(into)] and #[serde(from)] or #[serde(try_from)]",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn field(field: &syn::Field, container: &Container) -> Result<Field> {
+    let mut rename = None;
+    let mut aliases = Vec::new();
+    let mut default = None;
+    let mut skip = None;
+    let mut skip_serializing = None;
+    let mut skip_deserializing = None;
+    let mut skip_serializing_if = None;
+    let mut flatten = None;
+    let mut serialize_with = None;
+    let mut deserialize_with = None;
+
+    for meta in serde_meta(&field.attrs)? {
+        if let Some(value) = parse_string(&meta, "rename") {
+            set_once(&mut rename, value.value(), &meta, "rename")?;
+            continue;
+        }
+        if let Some(value) = parse_string(&meta, "alias") {
+            aliases.push(value.value());
+            continue;
+        }
+        if let Some(value) = parse_default(&meta)? {
+            set_once(&mut default, value, &meta, "default")?;
+            continue;
+        }
+        if parse_flag(&meta, "skip") {
+            set_once(&mut skip, (), &meta, "skip")?;
+            continue;
+        }
+        if parse_flag(&meta, "skip_serializing") {
+            set_once(&mut skip_serializing, (), &meta, "skip_serializing")?;
+            continue;
+        }
+        if parse_flag(&meta, "skip_deserializing") {
+            set_once(&mut skip_deserializing, (), &meta, "skip_deserializing")?;
+            continue;
+        }
+        if let Some(path) = parse_string(&meta, "skip_serializing_if") {
+            set_once(
+                &mut skip_serializing_if,
+                path.parse()?,
+                &meta,
+                "skip_serializing_if",
+            )?;
+            continue;
+        }
+        if parse_flag(&meta, "flatten") {
+            set_once(&mut flatten, meta.clone(), &meta, "flatten")?;
+            continue;
+        }
+        if let Some(path) = parse_string(&meta, "serialize_with") {
+            set_once(&mut serialize_with, path.parse()?, &meta, "serialize_with")?;
+            continue;
+        }
+        if let Some(path) = parse_string(&meta, "deserialize_with") {
+            set_once(
+                &mut deserialize_with,
+                path.parse()?,
+                &meta,
+                "deserialize_with",
+            )?;
+            continue;
+        }
+        if let Some(module) = parse_string(&meta, "with") {
+            let function =
+                |name| LitStr::new(&format!("{}::{}", module.value(), name), module.span());
+            set_once(
+                &mut serialize_with,
+                function("serialize").parse()?,
+                &meta,
+                "serialize_with",
+            )?;
+            set_once(
+                &mut deserialize_with,
+                function("deserialize").parse()?,
+                &meta,
+                "deserialize_with",
+            )?;
+            continue;
+        }
+        return Err(Error::new_spanned(meta, "unsupported attribute"));
+    }
+
+    if let Some(meta) = &flatten {
+        if serialize_with.is_some() || deserialize_with.is_some() {
+            return Err(Error::new_spanned(
+                meta,
+                "#[serde(flatten)] cannot be combined with a custom serializer or deserializer",
+            ));
+        }
+    }
+
+    Ok(Field {
+        name: rename.unwrap_or_else(|| {
+            let name = field.ident.as_ref().unwrap().to_string();
+            match container.rename_all {
+                Some(rule) => rule.apply_to_field(&name),
+                None => name,
+            }
+        }),
+        aliases,
+        default,
+        skip_serializing: skip.is_some() || skip_serializing.is_some(),
+        skip_deserializing: skip.is_some() || skip_deserializing.is_some(),
+        skip_serializing_if,
+        flatten: flatten.is_some(),
+        serialize_with,
+        deserialize_with,
+    })
+}
+
+pub fn variant(var: &syn::Variant, container: &Container) -> Result<Variant> {
     let mut rename = None;
+    let mut aliases = Vec::new();
+    let mut other = None;
+
+    for meta in serde_meta(&var.attrs)? {
+        if let Some(value) = parse_string(&meta, "rename") {
+            set_once(&mut rename, value.value(), &meta, "rename")?;
+            continue;
+        }
+        if let Some(value) = parse_string(&meta, "alias") {
+            aliases.push(value.value());
+            continue;
+        }
+        if parse_flag(&meta, "other") {
+            set_once(&mut other, meta.clone(), &meta, "other")?;
+            continue;
+        }
+        return Err(Error::new_spanned(meta, "unsupported attribute"));
+    }
+
+    // The catch-all variant is either a unit variant, or a newtype variant
+    // that holds on to the unrecognized name as a String.
+    if let Some(meta) = &other {
+        match &var.fields {
+            syn::Fields::Unit => {}
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                if !is_string(ty) {
+                    return Err(Error::new_spanned(
+                        ty,
+                        "#[serde(other)] on a newtype variant requires its field to be a String",
+                    ));
+                }
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "#[serde(other)] requires a unit variant or a String newtype variant",
+                ));
+            }
+        }
+        if let Tagging::Untagged = container.tagging {
+            return Err(Error::new_spanned(
+                meta,
+                "#[serde(other)] is not supported on untagged enums",
+            ));
+        }
+    }
+
+    Ok(Variant {
+        name: rename.unwrap_or_else(|| {
+            let name = var.ident.to_string();
+            match container.rename_all {
+                Some(rule) => rule.apply_to_variant(&name),
+                None => name,
+            }
+        }),
+        aliases,
+        other: other.is_some(),
+    })
+}
+
+/// Fields of a tuple variant do not support any attributes.
+pub fn unnamed_field(field: &syn::Field) -> Result<()> {
+    match serde_meta(&field.attrs)?.into_iter().next() {
+        Some(meta) => Err(Error::new_spanned(meta, "unsupported attribute")),
+        None => Ok(()),
+    }
+}
+
+/// Match `String`, or a path to it such as `std::string::String`.
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) if ty.qself.is_none() => match ty.path.segments.last() {
+            Some(segment) => segment.ident == "String" && segment.arguments.is_empty(),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// A #[serde(transparent)] struct must have exactly one field, which does not
+/// support any attributes.
+pub fn transparent_field(fields: &syn::FieldsNamed) -> Result<&syn::Field> {
+    if fields.named.len() != 1 {
+        return Err(Error::new_spanned(
+            fields,
+            "#[serde(transparent)] requires a struct with exactly one field",
+        ));
+    }
+    let field = &fields.named[0];
+    unnamed_field(field)?;
+    Ok(field)
+}
+
+/// Tuple structs, newtype structs and unit structs do not support any
+/// container attributes other than conversions.
+pub fn unnamed_struct(attrs: &[Attribute]) -> Result<()> {
+    let mut meta = serde_meta(attrs)?.into_iter();
+    match meta.find(|meta| !is_conversion(meta)) {
+        Some(meta) => Err(Error::new_spanned(meta, "unsupported attribute")),
+        None => Ok(()),
+    }
+}
+
+/// Collect the contents of every #[serde(...)] attribute.
+fn serde_meta(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    let mut nested = Vec::new();
 
     for attr in attrs {
         if !attr.path.is_ident("serde") {
             continue;
         }
 
-        let list = match attr.parse_meta()? {
-            Meta::List(list) => list,
+        match attr.parse_meta()? {
+            Meta::List(list) => nested.extend(list.nested),
             other => return Err(Error::new_spanned(other, "unsupported attribute")),
-        };
+        }
+    }
 
-        for meta in &list.nested {
-            if let NestedMeta::Meta(Meta::NameValue(value)) = meta {
-                if value.path.is_ident("rename") {
-                    if let Lit::Str(s) = &value.lit {
-                        if rename.is_some() {
-                            return Err(Error::new_spanned(meta, "duplicate rename attribute"));
-                        }
-                        rename = Some(s.value());
-                        continue;
-                    }
-                }
+    Ok(nested)
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, meta: &NestedMeta, name: &str) -> Result<()> {
+    if slot.is_some() {
+        let msg = format!("duplicate {} attribute", name);
+        return Err(Error::new_spanned(meta, msg));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Match a bare `name`.
+fn parse_flag(meta: &NestedMeta, name: &str) -> bool {
+    match meta {
+        NestedMeta::Meta(Meta::Path(path)) => path.is_ident(name),
+        _ => false,
+    }
+}
+
+/// Match `name = "..."`.
+fn parse_string<'a>(meta: &'a NestedMeta, name: &str) -> Option<&'a LitStr> {
+    if let NestedMeta::Meta(Meta::NameValue(value)) = meta {
+        if value.path.is_ident(name) {
+            if let Lit::Str(s) = &value.lit {
+                return Some(s);
             }
-            return Err(Error::new_spanned(meta, "unsupported attribute"));
         }
     }
-
-    Ok(rename)
+    None
 }
 
-/// Determine the name of a field, respecting a rename attribute.
-pub fn name_of_field(field: &Field) -> Result<String> {
-    let rename = attr_rename(&field.attrs)?;
-    Ok(rename.unwrap_or_else(|| field.ident.as_ref().unwrap().to_string()))
+/// Match `from = "..."`, `try_from = "..."` or `into = "..."`, which are
+/// handled by `conversions` rather than the other container attributes.
+fn is_conversion(meta: &NestedMeta) -> bool {
+    ["from", "try_from", "into"]
+        .iter()
+        .any(|name| parse_string(meta, name).is_some())
 }
 
-/// Determine the name of a variant, respecting a rename attribute.
-pub fn name_of_variant(var: &Variant) -> Result<String> {
-    let rename = attr_rename(&var.attrs)?;
-    Ok(rename.unwrap_or_else(|| var.ident.to_string()))
+/// Match `default` or `default = "path::to::function"`.
+fn parse_default(meta: &NestedMeta) -> Result<Option<Default>> {
+    if parse_flag(meta, "default") {
+        return Ok(Some(Default::Trait));
+    }
+    match parse_string(meta, "default") {
+        Some(path) => path.parse().map(Default::Path).map(Some),
+        None => Ok(None),
+    }
 }
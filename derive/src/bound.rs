@@ -36,6 +36,30 @@ pub fn with_lifetime_bound(generics: &Generics, lifetime: &str) -> Generics {
     }
 }
 
+// Prepends the `'__de` lifetime of the input being deserialized, which must
+// outlive every lifetime of `borrowed` so that those may borrow from the input.
+pub fn with_de_lifetime(generics: &Generics, borrowed: &Generics) -> Generics {
+    let def = LifetimeDef {
+        attrs: Vec::new(),
+        lifetime: Lifetime::new("'__de", Span::call_site()),
+        colon_token: None,
+        bounds: borrowed
+            .lifetimes()
+            .map(|def| def.lifetime.clone())
+            .collect(),
+    };
+
+    let params = Some(GenericParam::Lifetime(def))
+        .into_iter()
+        .chain(generics.params.iter().cloned())
+        .collect();
+
+    Generics {
+        params,
+        ..generics.clone()
+    }
+}
+
 pub fn where_clause_with_bound(generics: &Generics, bound: TokenStream) -> WhereClause {
     let new_predicates = generics.type_params().map::<WherePredicate, _>(|param| {
         let param = &param.ident;
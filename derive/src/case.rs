@@ -0,0 +1,102 @@
+use self::RenameRule::*;
+
+/// The different possible ways to change case of fields in a struct, or
+/// variants in an enum.
+#[derive(Copy, Clone)]
+pub enum RenameRule {
+    /// Rename to "lowercase" style.
+    Lower,
+    /// Rename to "UPPERCASE" style.
+    Upper,
+    /// Rename to "PascalCase" style.
+    Pascal,
+    /// Rename to "camelCase" style.
+    Camel,
+    /// Rename to "snake_case" style.
+    Snake,
+    /// Rename to "SCREAMING_SNAKE_CASE" style.
+    ScreamingSnake,
+    /// Rename to "kebab-case" style.
+    Kebab,
+    /// Rename to "SCREAMING-KEBAB-CASE" style.
+    ScreamingKebab,
+}
+
+static RENAME_RULES: &[(&str, RenameRule)] = &[
+    ("lowercase", Lower),
+    ("UPPERCASE", Upper),
+    ("PascalCase", Pascal),
+    ("camelCase", Camel),
+    ("snake_case", Snake),
+    ("SCREAMING_SNAKE_CASE", ScreamingSnake),
+    ("kebab-case", Kebab),
+    ("SCREAMING-KEBAB-CASE", ScreamingKebab),
+];
+
+impl RenameRule {
+    pub fn from_str(rule: &str) -> Option<Self> {
+        RENAME_RULES
+            .iter()
+            .find(|(name, _)| *name == rule)
+            .map(|(_, rule)| *rule)
+    }
+
+    /// Apply a renaming rule to an enum variant, which is assumed to be written
+    /// in Pascal.
+    pub fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            Pascal => variant.to_owned(),
+            Lower => variant.to_ascii_lowercase(),
+            Upper => variant.to_ascii_uppercase(),
+            Camel => lowercase_first(variant),
+            Snake => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            ScreamingSnake => Snake.apply_to_variant(variant).to_ascii_uppercase(),
+            Kebab => Snake.apply_to_variant(variant).replace('_', "-"),
+            ScreamingKebab => ScreamingSnake.apply_to_variant(variant).replace('_', "-"),
+        }
+    }
+
+    /// Apply a renaming rule to a struct field, which is assumed to be written
+    /// in snake_case.
+    pub fn apply_to_field(self, field: &str) -> String {
+        match self {
+            Lower | Snake => field.to_owned(),
+            Upper | ScreamingSnake => field.to_ascii_uppercase(),
+            Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            Camel => lowercase_first(&Pascal.apply_to_field(field)),
+            Kebab => field.replace('_', "-"),
+            ScreamingKebab => ScreamingSnake.apply_to_field(field).replace('_', "-"),
+        }
+    }
+}
+
+fn lowercase_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -1,21 +1,32 @@
 use crate::{attr, bound};
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{
-    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Ident, Result,
+    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed,
+    FieldsUnnamed, Ident, Result, Type, WhereClause,
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let conversions = attr::conversions(&input.attrs)?;
+    if let Some(from) = &conversions.from {
+        attr::check_from(&input.attrs, &conversions)?;
+        return derive_from(&input, from);
+    }
+
     match &input.data {
         Data::Struct(DataStruct {
             fields: Fields::Named(fields),
             ..
         }) => derive_struct(&input, fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(fields),
+            ..
+        }) => derive_tuple_struct(&input, fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => derive_unit_struct(&input),
         Data::Enum(enumeration) => derive_enum(&input, enumeration),
-        Data::Struct(_) => Err(Error::new(
-            Span::call_site(),
-            "currently only structs with named fields are supported",
-        )),
         Data::Union(_) => Err(Error::new(
             Span::call_site(),
             "currently only structs and enums are supported by this derive",
@@ -25,150 +36,1179 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
 
 pub fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStream> {
     let ident = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let dummy = Ident::new(
         &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
         Span::call_site(),
     );
 
-    let fieldname = fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
-    let fieldty = fields.named.iter().map(|f| &f.ty);
-    let fieldstr = fields
-        .named
-        .iter()
-        .map(attr::name_of_field)
-        .collect::<Result<Vec<_>>>()?;
+    let container = attr::container(&input.attrs)?;
+    match container.tagging {
+        attr::Tagging::External => {}
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "#[serde(tag)], #[serde(content)], #[serde(untagged)] and #[serde(repr)] are only supported on enums",
+            ));
+        }
+    }
+    let bound = parse_quote!(miniserde::Deserialize<'__de>);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
 
-    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
-    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
-    let bound = parse_quote!(miniserde::Deserialize);
+    // A transparent struct is deserialized from whatever its one field is
+    // deserialized from, like a newtype struct.
+    let build = if container.transparent {
+        let field = attr::transparent_field(fields)?;
+        let name = &field.ident;
+        let ty = &field.ty;
+        let wrap = quote! {
+            |__value: #ty| miniserde::__private::Ok(#ident { #name: __value })
+        };
+        deserialize_as(input, wrap, &bounded_where_clause)
+    } else {
+        let visitor = Ident::new("__Visitor", Span::call_site());
+        let state = Ident::new("__State", Span::call_site());
+        deserialize_struct(
+            input,
+            &container,
+            &visitor,
+            &state,
+            quote!(#ident),
+            &ident.to_string(),
+            fields,
+        )?
+    };
+
+    let begin = impl_begin(input, &bounded_where_clause);
+    Ok(quote! {
+        #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
+        const #dummy: () = {
+            #begin
+
+            #build
+        };
+    })
+}
+
+// A tuple struct is deserialized from an array, except for a newtype struct
+// which is deserialized from whatever its field is deserialized from.
+fn derive_tuple_struct(input: &DeriveInput, fields: &FieldsUnnamed) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let dummy = Ident::new(
+        &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+
+    attr::unnamed_struct(&input.attrs)?;
+    let bound = parse_quote!(miniserde::Deserialize<'__de>);
     let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
 
+    let build = if fields.unnamed.len() == 1 {
+        attr::unnamed_field(&fields.unnamed[0])?;
+        let ty = &fields.unnamed[0].ty;
+        let wrap = quote! {
+            |__value: #ty| miniserde::__private::Ok(#ident(__value))
+        };
+        deserialize_as(input, wrap, &bounded_where_clause)
+    } else {
+        let visitor = Ident::new("__Visitor", Span::call_site());
+        let state = Ident::new("__State", Span::call_site());
+        deserialize_tuple(
+            input,
+            &visitor,
+            &state,
+            quote!(#ident),
+            &ident.to_string(),
+            fields,
+        )?
+    };
+
+    let begin = impl_begin(input, &bounded_where_clause);
+    Ok(quote! {
+        #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
+        const #dummy: () = {
+            #begin
+
+            #build
+        };
+    })
+}
+
+// A container with #[serde(from = "...")] or #[serde(try_from = "...")] is
+// deserialized as the other type and then converted.
+fn derive_from(input: &DeriveInput, from: &attr::From) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+
+    let wrap = match from {
+        attr::From::Infallible(ty) => quote! {
+            |__value: #ty| miniserde::__private::Ok(<#ident #ty_generics as miniserde::__private::From<#ty>>::from(__value))
+        },
+        attr::From::Fallible(ty) => {
+            let identstr = ident.to_string();
+            quote! {
+                |__value: #ty| <#ident #ty_generics as miniserde::__private::TryFrom<#ty>>::try_from(__value).map_err(|__err| miniserde::Error::invalid_value(__err, #identstr))
+            }
+        }
+    };
+    let ty = match from {
+        attr::From::Infallible(ty) | attr::From::Fallible(ty) => ty,
+    };
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#ty: miniserde::Deserialize<'__de>));
+    let where_clause = generics.where_clause.unwrap();
+    let build = deserialize_as(input, wrap, &where_clause);
+
+    let begin = impl_begin(input, &where_clause);
+    Ok(quote! {
+        #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
+        const #dummy: () = {
+            #begin
+
+            #build
+        };
+    })
+}
+
+// Generates a #[repr(C)] visitor type wrapping Option<#ident> which forwards
+// every event to the visitor of some other type, then passes the result
+// through `wrap` to build the container.
+fn deserialize_as(
+    input: &DeriveInput,
+    wrap: TokenStream,
+    bounded_where_clause: &WhereClause,
+) -> TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
+    let scalar = |method: &str, arg: Option<TokenStream>| {
+        let method = Ident::new(method, Span::call_site());
+        let param = arg.as_ref().map(|ty| quote!(__arg: #ty));
+        let arg = arg.map(|_| quote!(__arg));
+        quote! {
+            fn #method(&mut self, #param) -> miniserde::Result<()> {
+                miniserde::__private::convert(&mut self.__out, #wrap, |__v| __v.#method(#arg))
+            }
+        }
+    };
+    let null = scalar("null", None);
+    let boolean = scalar("boolean", Some(quote!(bool)));
+    let string = scalar("string", Some(quote!(&miniserde::__private::str)));
+    let borrowed_string = scalar(
+        "borrowed_string",
+        Some(quote!(&'__de miniserde::__private::str)),
+    );
+    let negative = scalar("negative", Some(quote!(i64)));
+    let nonnegative = scalar("nonnegative", Some(quote!(u64)));
+    let float = scalar("float", Some(quote!(f64)));
+
+    quote! {
+        #[repr(C)]
+        struct __Visitor #impl_generics #where_clause {
+            __out: miniserde::__private::Option<#ident #ty_generics>,
+        }
+
+        impl #de_impl_generics miniserde::de::Visitor<'__de> for __Visitor #ty_generics #bounded_where_clause {
+            #null
+            #boolean
+            #string
+            #borrowed_string
+            #negative
+            #nonnegative
+            #float
+
+            fn seq<'__a>(&'__a mut self) -> miniserde::Result<miniserde::__private::Box<dyn miniserde::de::Seq<'__de> + '__a>> where '__de: '__a {
+                miniserde::__private::convert_seq(&mut self.__out, #wrap)
+            }
+
+            fn map<'__a>(&'__a mut self) -> miniserde::Result<miniserde::__private::Box<dyn miniserde::de::Map<'__de> + '__a>> where '__de: '__a {
+                miniserde::__private::convert_map(&mut self.__out, #wrap)
+            }
+        }
+    }
+}
+
+// A unit struct is deserialized from null.
+fn derive_unit_struct(input: &DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+
+    attr::unnamed_struct(&input.attrs)?;
+
+    let begin = impl_begin(input, &where_clause);
     Ok(quote! {
-        #[allow(non_upper_case_globals)]
+        #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
         const #dummy: () = {
             #[repr(C)]
             struct __Visitor #impl_generics #where_clause {
                 __out: miniserde::__private::Option<#ident #ty_generics>,
             }
 
-            impl #impl_generics miniserde::Deserialize for #ident #ty_generics #bounded_where_clause {
-                fn begin(__out: &mut miniserde::__private::Option<Self>) -> &mut dyn miniserde::de::Visitor {
-                    unsafe {
-                        &mut *{
-                            __out
-                            as *mut miniserde::__private::Option<Self>
-                            as *mut __Visitor #ty_generics
-                        }
-                    }
+            #begin
+
+            impl #de_impl_generics miniserde::de::Visitor<'__de> for __Visitor #ty_generics #where_clause {
+                fn null(&mut self) -> miniserde::Result<()> {
+                    self.__out = miniserde::__private::Some(#ident);
+                    miniserde::__private::Ok(())
                 }
             }
+        };
+    })
+}
 
-            impl #impl_generics miniserde::de::Visitor for __Visitor #ty_generics #bounded_where_clause {
-                fn map(&mut self) -> miniserde::Result<miniserde::__private::Box<dyn miniserde::de::Map + '_>> {
-                    Ok(miniserde::__private::Box::new(__State {
-                        #(
-                            #fieldname: miniserde::Deserialize::default(),
-                        )*
-                        __out: &mut self.__out,
-                    }))
+// Generates a #[repr(C)] visitor type wrapping Option<#ident> which builds the
+// braced struct or struct variant at `path` out of a map.
+fn deserialize_struct(
+    input: &DeriveInput,
+    container: &attr::Container,
+    visitor: &Ident,
+    state: &Ident,
+    path: TokenStream,
+    identstr: &str,
+    fields: &FieldsNamed,
+) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
+    let attrs = fields
+        .named
+        .iter()
+        .map(|field| attr::field(field, container))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut fieldname = Vec::new();
+    let mut fieldty = Vec::new();
+    let mut fieldpat = Vec::new();
+    let mut fieldinit = Vec::new();
+    let mut fieldbegin = Vec::new();
+    let mut fieldvalue = Vec::new();
+    let mut skippedname = Vec::new();
+    let mut skippedvalue = Vec::new();
+    let mut flattenname = Vec::new();
+    for (field, attrs) in fields.named.iter().zip(&attrs) {
+        let name = &field.ident;
+        if attrs.skip_deserializing {
+            skippedname.push(name);
+            skippedvalue.push(match (&attrs.default, &container.default) {
+                (Some(attr::Default::Path(path)), _) => quote!(#path()),
+                (None, Some(_)) => quote!(__default.#name),
+                (Some(attr::Default::Trait), _) | (None, None) => {
+                    quote!(miniserde::__private::Default::default())
+                }
+            });
+            continue;
+        }
+        if attrs.flatten {
+            flattenname.push(name);
+            continue;
+        }
+        fieldname.push(name);
+        fieldty.push(&field.ty);
+        let aliases = &attrs.aliases;
+        let fieldstr = &attrs.name;
+        fieldpat.push(quote!(#fieldstr #(| #aliases)*));
+        fieldinit.push(
+            if attrs.default.is_some()
+                || container.default.is_some()
+                || attrs.deserialize_with.is_some()
+            {
+                quote!(miniserde::__private::None)
+            } else {
+                quote!(miniserde::Deserialize::default())
+            },
+        );
+        fieldbegin.push(match &attrs.deserialize_with {
+            Some(path) => quote!(#path(&mut self.#name)),
+            None => quote!(miniserde::Deserialize::begin(&mut self.#name)),
+        });
+        fieldvalue.push(match (&attrs.default, &container.default) {
+            (Some(attr::Default::Trait), _) => quote! {
+                self.#name.take().unwrap_or_else(miniserde::__private::Default::default)
+            },
+            (Some(attr::Default::Path(path)), _) => quote! {
+                self.#name.take().unwrap_or_else(#path)
+            },
+            (None, Some(_)) => quote! {
+                self.#name.take().unwrap_or(__default.#name)
+            },
+            (None, None) => {
+                let fieldstr = &attrs.name;
+                quote! {
+                    self.#name.take().ok_or_else(|| miniserde::Error::missing_field(#fieldstr, #identstr))?
                 }
             }
+        });
+    }
+    let (buffer, bufferinit) = if flattenname.is_empty() {
+        (None, None)
+    } else {
+        (
+            Some(quote!(__flatten: miniserde::__private::FlattenBuffer<'__de>,)),
+            Some(quote!(__flatten: miniserde::__private::Default::default(),)),
+        )
+    };
+    let unknown = if !flattenname.is_empty() {
+        if container.deny_unknown_fields {
+            return Err(Error::new(
+                Span::call_site(),
+                "#[serde(flatten)] cannot be combined with #[serde(deny_unknown_fields)]",
+            ));
+        }
+        Some(quote!(miniserde::__private::Ok(self.__flatten.key(__k))))
+    } else if container.deny_unknown_fields {
+        Some(quote!(miniserde::__private::Err(miniserde::Error::unknown_field(__k, #identstr))))
+    } else {
+        None
+    };
+    // A struct that ignores unknown keys says so through __flatten_key, so
+    // that when it is flattened into another, those keys are left for the
+    // other's remaining fields.
+    let key = match unknown {
+        Some(unknown) => quote! {
+            fn key(&mut self, __k: &miniserde::__private::str) -> miniserde::Result<&mut dyn miniserde::de::Visitor<'__de>> {
+                match __k {
+                    #(
+                        #fieldpat => miniserde::__private::Ok(#fieldbegin),
+                    )*
+                    _ => #unknown,
+                }
+            }
+        },
+        None => quote! {
+            fn key(&mut self, __k: &miniserde::__private::str) -> miniserde::Result<&mut dyn miniserde::de::Visitor<'__de>> {
+                match self.__flatten_key(__k)? {
+                    miniserde::__private::Some(__visitor) => miniserde::__private::Ok(__visitor),
+                    miniserde::__private::None => miniserde::__private::Ok(<dyn miniserde::de::Visitor>::ignore()),
+                }
+            }
+
+            fn __flatten_key(&mut self, __k: &miniserde::__private::str) -> miniserde::Result<miniserde::__private::Option<&mut dyn miniserde::de::Visitor<'__de>>> {
+                match __k {
+                    #(
+                        #fieldpat => miniserde::__private::Ok(miniserde::__private::Some(#fieldbegin)),
+                    )*
+                    _ => miniserde::__private::Ok(miniserde::__private::None),
+                }
+            }
+        },
+    };
+    let default = match &container.default {
+        Some(attr::Default::Trait) => quote! {
+            let __default: #ident #ty_generics = miniserde::__private::Default::default();
+        },
+        Some(attr::Default::Path(path)) => quote! {
+            let __default: #ident #ty_generics = #path();
+        },
+        None => quote!(),
+    };
+
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let wrapper_de_generics = bound::with_de_lifetime(&wrapper_generics, &input.generics);
+    let (wrapper_de_impl_generics, wrapper_de_ty_generics, _) =
+        wrapper_de_generics.split_for_impl();
+    let bound = parse_quote!(miniserde::Deserialize<'__de>);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+
+    // The flatten buffer holds on to strings borrowed from the input, so the
+    // state needs to name the input lifetime.
+    let (state_impl_generics, state_ty_generics) = if flattenname.is_empty() {
+        (wrapper_impl_generics, wrapper_ty_generics)
+    } else {
+        (wrapper_de_impl_generics.clone(), wrapper_de_ty_generics)
+    };
+
+    Ok(quote! {
+        #[repr(C)]
+        struct #visitor #impl_generics #where_clause {
+            __out: miniserde::__private::Option<#ident #ty_generics>,
+        }
+
+        impl #de_impl_generics miniserde::de::Visitor<'__de> for #visitor #ty_generics #bounded_where_clause {
+            fn map<'__a>(&'__a mut self) -> miniserde::Result<miniserde::__private::Box<dyn miniserde::de::Map<'__de> + '__a>> where '__de: '__a {
+                Ok(miniserde::__private::Box::new(#state {
+                    #(
+                        #fieldname: #fieldinit,
+                    )*
+                    #bufferinit
+                    __out: &mut self.__out,
+                }))
+            }
+        }
 
-            struct __State #wrapper_impl_generics #where_clause {
+        struct #state #state_impl_generics #where_clause {
+            #(
+                #fieldname: miniserde::__private::Option<#fieldty>,
+            )*
+            #buffer
+            __out: &'__a mut miniserde::__private::Option<#ident #ty_generics>,
+        }
+
+        impl #wrapper_de_impl_generics miniserde::de::Map<'__de> for #state #state_ty_generics #bounded_where_clause {
+            #key
+
+            fn finish(&mut self) -> miniserde::Result<()> {
+                #default
                 #(
-                    #fieldname: miniserde::__private::Option<#fieldty>,
+                    let #fieldname = #fieldvalue;
                 )*
-                __out: &'__a mut miniserde::__private::Option<#ident #ty_generics>,
+                #(
+                    let #flattenname = self.__flatten.deserialize()?;
+                )*
+                #(
+                    let #skippedname = #skippedvalue;
+                )*
+                *self.__out = miniserde::__private::Some(#path {
+                    #(
+                        #fieldname,
+                    )*
+                    #(
+                        #flattenname,
+                    )*
+                    #(
+                        #skippedname,
+                    )*
+                });
+                miniserde::__private::Ok(())
             }
+        }
+    })
+}
 
-            impl #wrapper_impl_generics miniserde::de::Map for __State #wrapper_ty_generics #bounded_where_clause {
-                fn key(&mut self, __k: &miniserde::__private::str) -> miniserde::Result<&mut dyn miniserde::de::Visitor> {
-                    match __k {
-                        #(
-                            #fieldstr => miniserde::__private::Ok(miniserde::Deserialize::begin(&mut self.#fieldname)),
-                        )*
-                        _ => miniserde::__private::Ok(<dyn miniserde::de::Visitor>::ignore()),
-                    }
-                }
+// Generates a #[repr(C)] visitor type wrapping Option<#ident> which builds the
+// tuple struct or tuple variant at `path` out of a sequence.
+fn deserialize_tuple(
+    input: &DeriveInput,
+    visitor: &Ident,
+    state: &Ident,
+    path: TokenStream,
+    identstr: &str,
+    fields: &FieldsUnnamed,
+) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
+    for field in &fields.unnamed {
+        attr::unnamed_field(field)?;
+    }
+    let fieldname = (0..fields.unnamed.len())
+        .map(|i| Ident::new(&format!("__field{}", i), Span::call_site()))
+        .collect::<Vec<_>>();
+    let fieldty = fields.unnamed.iter().map(|field| &field.ty);
+    let index = 0usize..;
+    let indexstr = (0..fields.unnamed.len()).map(|i| i.to_string());
+
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let wrapper_de_generics = bound::with_de_lifetime(&wrapper_generics, &input.generics);
+    let (wrapper_de_impl_generics, _, _) = wrapper_de_generics.split_for_impl();
+    let bound = parse_quote!(miniserde::Deserialize<'__de>);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+
+    Ok(quote! {
+        #[repr(C)]
+        struct #visitor #impl_generics #where_clause {
+            __out: miniserde::__private::Option<#ident #ty_generics>,
+        }
 
-                fn finish(&mut self) -> miniserde::Result<()> {
+        impl #de_impl_generics miniserde::de::Visitor<'__de> for #visitor #ty_generics #bounded_where_clause {
+            fn seq<'__a>(&'__a mut self) -> miniserde::Result<miniserde::__private::Box<dyn miniserde::de::Seq<'__de> + '__a>> where '__de: '__a {
+                Ok(miniserde::__private::Box::new(#state {
                     #(
-                        let #fieldname = self.#fieldname.take().ok_or(miniserde::Error)?;
+                        #fieldname: miniserde::__private::None,
                     )*
-                    *self.__out = miniserde::__private::Some(#ident {
-                        #(
-                            #fieldname,
-                        )*
-                    });
-                    miniserde::__private::Ok(())
+                    __index: 0,
+                    __out: &mut self.__out,
+                }))
+            }
+        }
+
+        struct #state #wrapper_impl_generics #where_clause {
+            #(
+                #fieldname: miniserde::__private::Option<#fieldty>,
+            )*
+            __index: miniserde::__private::usize,
+            __out: &'__a mut miniserde::__private::Option<#ident #ty_generics>,
+        }
+
+        impl #wrapper_de_impl_generics miniserde::de::Seq<'__de> for #state #wrapper_ty_generics #bounded_where_clause {
+            fn element(&mut self) -> miniserde::Result<&mut dyn miniserde::de::Visitor<'__de>> {
+                let __index = self.__index;
+                self.__index = __index + 1;
+                match __index {
+                    #(
+                        #index => miniserde::__private::Ok(miniserde::Deserialize::begin(&mut self.#fieldname)),
+                    )*
+                    _ => miniserde::__private::Err(miniserde::Error),
                 }
             }
-        };
+
+            fn finish(&mut self) -> miniserde::Result<()> {
+                #(
+                    let #fieldname = self.#fieldname.take().ok_or_else(|| miniserde::Error::missing_field(#indexstr, #identstr))?;
+                )*
+                *self.__out = miniserde::__private::Some(#path(#(#fieldname),*));
+                miniserde::__private::Ok(())
+            }
+        }
     })
 }
 
 pub fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
-    if input.generics.lt_token.is_some() || input.generics.where_clause.is_some() {
-        return Err(Error::new(
-            Span::call_site(),
-            "Enums with generics are not supported",
-        ));
-    }
-
     let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
     let dummy = Ident::new(
         &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
         Span::call_site(),
     );
+    let bound = parse_quote!(miniserde::Deserialize<'__de>);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
 
-    let var_idents = enumeration
-        .variants
-        .iter()
-        .map(|variant| match variant.fields {
-            Fields::Unit => Ok(&variant.ident),
-            _ => Err(Error::new_spanned(
-                variant,
-                "Invalid variant: only simple enum variants without fields are supported",
-            )),
-        })
-        .collect::<Result<Vec<_>>>()?;
-    let names = enumeration
-        .variants
-        .iter()
-        .map(attr::name_of_variant)
-        .collect::<Result<Vec<_>>>()?;
+    let container = attr::container(&input.attrs)?;
+    if container.default.is_some() {
+        return Err(Error::new(
+            Span::call_site(),
+            "#[serde(default)] is only supported on structs",
+        ));
+    }
+    if container.transparent {
+        return Err(Error::new(
+            Span::call_site(),
+            "#[serde(transparent)] is only supported on structs",
+        ));
+    }
+    let variant_container = container.variant_fields();
+    if let attr::Tagging::Int = container.tagging {
+        for variant in &enumeration.variants {
+            if let Fields::Named(_) | Fields::Unnamed(_) = variant.fields {
+                return Err(Error::new_spanned(
+                    variant,
+                    "#[serde(repr = \"int\")] supports only unit variants",
+                ));
+            }
+        }
+    }
+    let internal = match container.tagging {
+        attr::Tagging::Internal { .. } => true,
+        _ => false,
+    };
+
+    let mut variants = Variants {
+        other: None,
+        unit: Vec::new(),
+        newtype: Vec::new(),
+        content: Vec::new(),
+    };
+    let mut content_build = Vec::new();
+    let mut untagged_attempt = Vec::new();
+    for (i, variant) in enumeration.variants.iter().enumerate() {
+        let attrs = attr::variant(variant, &container)?;
+        let name = &attrs.name;
+        let aliases = &attrs.aliases;
+        let pat = quote!(#name #(| #aliases)*);
+        let var_ident = &variant.ident;
+        // Any unrecognized variant name `__name` deserializes as the
+        // #[serde(other)] variant.
+        if attrs.other {
+            if variants.other.is_some() {
+                return Err(Error::new_spanned(
+                    variant,
+                    "only one variant can be #[serde(other)]",
+                ));
+            }
+            if let Fields::Unnamed(fields) = &variant.fields {
+                attr::unnamed_field(&fields.unnamed[0])?;
+                variants.other = Some(quote! {
+                    #ident::#var_ident(miniserde::__private::String::from(__name))
+                });
+                continue;
+            }
+            variants.other = Some(quote!(#ident::#var_ident));
+        }
+        let visitor = Ident::new(&format!("__Visitor{}", i), Span::call_site());
+        let state = Ident::new(&format!("__State{}", i), Span::call_site());
+        match &variant.fields {
+            Fields::Unit => {
+                untagged_attempt.push(quote! {
+                    if let miniserde::json::Value::Null = __value {
+                        self.__out = miniserde::__private::Some(#ident::#var_ident);
+                        return miniserde::__private::Ok(());
+                    }
+                });
+                variants.unit.push(UnitVariant {
+                    ident: var_ident,
+                    pat,
+                });
+            }
+            Fields::Unnamed(_) if internal => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "internally tagged enums support only unit and struct variants",
+                ));
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                attr::unnamed_field(&fields.unnamed[0])?;
+                let ty = &fields.unnamed[0].ty;
+                untagged_attempt.push(quote! {
+                    let mut __newtype: miniserde::__private::Option<#ty> = miniserde::__private::None;
+                    if miniserde::de::replay(&__value, miniserde::Deserialize::begin(&mut __newtype)).is_ok() {
+                        if let miniserde::__private::Some(__newtype) = __newtype {
+                            self.__out = miniserde::__private::Some(#ident::#var_ident(__newtype));
+                            return miniserde::__private::Ok(());
+                        }
+                    }
+                });
+                variants.newtype.push(NewtypeVariant {
+                    ident: var_ident,
+                    pat,
+                    place: Ident::new(&format!("__newtype{}", i), Span::call_site()),
+                    ty,
+                });
+            }
+            Fields::Unnamed(fields) => {
+                content_build.push(deserialize_tuple(
+                    input,
+                    &visitor,
+                    &state,
+                    quote!(#ident::#var_ident),
+                    &format!("{}::{}", ident, var_ident),
+                    fields,
+                )?);
+                untagged_attempt.push(untagged_content(input, &visitor));
+                variants.content.push(ContentVariant { pat, visitor });
+            }
+            Fields::Named(fields) => {
+                for field in &fields.named {
+                    if attr::field(field, &variant_container)?.flatten {
+                        return Err(Error::new_spanned(
+                            field,
+                            "#[serde(flatten)] is not supported in enum variants",
+                        ));
+                    }
+                }
+                content_build.push(deserialize_struct(
+                    input,
+                    &variant_container,
+                    &visitor,
+                    &state,
+                    quote!(#ident::#var_ident),
+                    &format!("{}::{}", ident, var_ident),
+                    fields,
+                )?);
+                untagged_attempt.push(untagged_content(input, &visitor));
+                variants.content.push(ContentVariant { pat, visitor });
+            }
+        }
+    }
 
+    let body = match &container.tagging {
+        attr::Tagging::External => externally_tagged(input, &variants),
+        attr::Tagging::Internal { tag } => internally_tagged(input, &variants, tag),
+        attr::Tagging::Adjacent { tag, content } => {
+            adjacently_tagged(input, &variants, tag, content)
+        }
+        attr::Tagging::Int => int_discriminant(input, &variants),
+        attr::Tagging::Untagged => quote! {
+            impl #de_impl_generics miniserde::de::Buffered for __Visitor #ty_generics #bounded_where_clause {
+                fn buffered(&mut self, __value: miniserde::json::Value) -> miniserde::Result<()> {
+                    #(
+                        {
+                            #untagged_attempt
+                        }
+                    )*
+                    miniserde::__private::Err(miniserde::Error)
+                }
+            }
+        },
+    };
+
+    let begin = impl_begin(input, &bounded_where_clause);
     Ok(quote! {
-        #[allow(non_upper_case_globals)]
+        #[allow(non_upper_case_globals, unreachable_patterns, unknown_lints, non_local_definitions)]
         const #dummy: () = {
             #[repr(C)]
-            struct __Visitor {
-                __out: miniserde::__private::Option<#ident>,
+            struct __Visitor #impl_generics #where_clause {
+                __out: miniserde::__private::Option<#ident #ty_generics>,
             }
 
-            impl miniserde::Deserialize for #ident {
-                fn begin(__out: &mut miniserde::__private::Option<Self>) -> &mut dyn miniserde::de::Visitor {
-                    unsafe {
-                        &mut *{
-                            __out
-                            as *mut miniserde::__private::Option<Self>
-                            as *mut __Visitor
-                        }
+            #begin
+
+            #body
+
+            #(
+                #content_build
+            )*
+        };
+    })
+}
+
+// The variants of an enum, grouped by how their data is deserialized.
+struct Variants<'a> {
+    // The #[serde(other)] variant, built out of the unrecognized `__name`.
+    other: Option<TokenStream>,
+    unit: Vec<UnitVariant<'a>>,
+    newtype: Vec<NewtypeVariant<'a>>,
+    content: Vec<ContentVariant>,
+}
+
+// A variant without data.
+struct UnitVariant<'a> {
+    ident: &'a Ident,
+    // Matches the variant's name and its aliases.
+    pat: TokenStream,
+}
+
+// A variant with one unnamed field, whose data is held aside in `place` until
+// the variant can be built.
+struct NewtypeVariant<'a> {
+    ident: &'a Ident,
+    pat: TokenStream,
+    place: Ident,
+    ty: &'a Type,
+}
+
+// A tuple or struct variant, whose data is built directly into an Option<Self>
+// by the variant's own `visitor`.
+struct ContentVariant {
+    pat: TokenStream,
+    visitor: Ident,
+}
+
+// The visitor of an externally tagged enum, which is either the name of a unit
+// variant or a map whose only key is the name of a variant with data.
+fn externally_tagged(input: &DeriveInput, variants: &Variants) -> TokenStream {
+    let ident = &input.ident;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let wrapper_de_generics = bound::with_de_lifetime(&wrapper_generics, &input.generics);
+    let (wrapper_de_impl_generics, _, _) = wrapper_de_generics.split_for_impl();
+    let bound = parse_quote!(miniserde::Deserialize<'__de>);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+    let other = variants.other.as_ref();
+    let string = if variants.unit.is_empty() && other.is_none() {
+        None
+    } else {
+        let unit_arm = variants.unit.iter().map(|variant| {
+            let pat = &variant.pat;
+            let var_ident = variant.ident;
+            quote!(#pat => #ident::#var_ident,)
+        });
+        let fallback = match other {
+            Some(other) => quote!(__name => #other,),
+            None => quote!(_ => return miniserde::__private::Err(miniserde::Error),),
+        };
+        Some(quote! {
+            fn string(&mut self, s: &miniserde::__private::str) -> miniserde::Result<()> {
+                let value = match s {
+                    #(#unit_arm)*
+                    #fallback
+                };
+                self.__out = miniserde::__private::Some(value);
+                miniserde::__private::Ok(())
+            }
+        })
+    };
+
+    // A variant with data is a map with the variant name as its only key. The
+    // data of a tuple or struct variant is built directly into an Option<Self>
+    // by that variant's own visitor, while newtype data is held aside until
+    // the map is finished.
+    if variants.newtype.is_empty() && variants.content.is_empty() && other.is_none() {
+        return quote! {
+            impl #de_impl_generics miniserde::de::Visitor<'__de> for __Visitor #ty_generics #bounded_where_clause {
+                #string
+            }
+        };
+    }
+    // A unit variant is never written as a map, so its name is rejected here
+    // as it would be without #[serde(other)]. Only the data of an unrecognized
+    // variant is ignored.
+    let unit_key = variants.unit.iter().map(|variant| &variant.pat);
+    let key_fallback = match other {
+        Some(other) => quote! {
+            __name => {
+                self.__content = miniserde::__private::Some(#other);
+                miniserde::__private::Ok(<dyn miniserde::de::Visitor>::ignore())
+            }
+        },
+        None => quote!(_ => miniserde::__private::Err(miniserde::Error),),
+    };
+    let newtype_init = variants.newtype.iter().map(|variant| {
+        let place = &variant.place;
+        quote!(#place: miniserde::__private::None,)
+    });
+    let newtype_field = variants.newtype.iter().map(|variant| {
+        let place = &variant.place;
+        let ty = variant.ty;
+        quote!(#place: miniserde::__private::Option<#ty>,)
+    });
+    let newtype_arm = variants.newtype.iter().map(|variant| {
+        let pat = &variant.pat;
+        let place = &variant.place;
+        quote! {
+            #pat => miniserde::__private::Ok(miniserde::Deserialize::begin(&mut self.#place)),
+        }
+    });
+    let content_arm = variants.content.iter().map(|variant| {
+        let pat = &variant.pat;
+        let cast = visitor_cast(input, quote!(&mut self.__content), &variant.visitor);
+        quote! {
+            #pat => miniserde::__private::Ok(#cast),
+        }
+    });
+    let newtype_finish = variants.newtype.iter().map(|variant| {
+        let place = &variant.place;
+        let var_ident = variant.ident;
+        quote! {
+            if let miniserde::__private::Some(__value) = self.#place.take() {
+                *self.__out = miniserde::__private::Some(#ident::#var_ident(__value));
+                return miniserde::__private::Ok(());
+            }
+        }
+    });
+
+    quote! {
+        impl #de_impl_generics miniserde::de::Visitor<'__de> for __Visitor #ty_generics #bounded_where_clause {
+            #string
+
+            fn map<'__a>(&'__a mut self) -> miniserde::Result<miniserde::__private::Box<dyn miniserde::de::Map<'__de> + '__a>> where '__de: '__a {
+                Ok(miniserde::__private::Box::new(__Variant {
+                    __out: &mut self.__out,
+                    __seen: false,
+                    __content: miniserde::__private::None,
+                    #(#newtype_init)*
+                }))
+            }
+        }
+
+        struct __Variant #wrapper_impl_generics #where_clause {
+            __out: &'__a mut miniserde::__private::Option<#ident #ty_generics>,
+            __seen: bool,
+            __content: miniserde::__private::Option<#ident #ty_generics>,
+            #(#newtype_field)*
+        }
+
+        impl #wrapper_de_impl_generics miniserde::de::Map<'__de> for __Variant #wrapper_ty_generics #bounded_where_clause {
+            fn key(&mut self, __k: &miniserde::__private::str) -> miniserde::Result<&mut dyn miniserde::de::Visitor<'__de>> {
+                if self.__seen {
+                    return miniserde::__private::Err(miniserde::Error);
+                }
+                self.__seen = true;
+                match __k {
+                    #(#newtype_arm)*
+                    #(#content_arm)*
+                    #(#unit_key => miniserde::__private::Err(miniserde::Error),)*
+                    #key_fallback
+                }
+            }
+
+            fn finish(&mut self) -> miniserde::Result<()> {
+                #(#newtype_finish)*
+                match self.__content.take() {
+                    miniserde::__private::Some(__value) => {
+                        *self.__out = miniserde::__private::Some(__value);
+                        miniserde::__private::Ok(())
                     }
+                    miniserde::__private::None => miniserde::__private::Err(miniserde::Error),
                 }
             }
+        }
+    }
+}
 
-            impl miniserde::de::Visitor for __Visitor {
-                fn string(&mut self, s: &miniserde::__private::str) -> miniserde::Result<()> {
-                    let value = match s {
-                        #( #names => #ident::#var_idents, )*
-                        _ => return miniserde::__private::Err(miniserde::Error),
-                    };
-                    self.__out = miniserde::__private::Some(value);
-                    miniserde::__private::Ok(())
+// The visitor of an internally tagged enum, which is a map holding the variant
+// name under the tag key alongside the fields of the variant.
+fn internally_tagged(input: &DeriveInput, variants: &Variants, tag: &str) -> TokenStream {
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let bound = parse_quote!(miniserde::Deserialize<'__de>);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+    let identstr = ident.to_string();
+    let unit_arm = variants.unit.iter().map(|variant| {
+        let pat = &variant.pat;
+        let var_ident = variant.ident;
+        quote! {
+            #pat => {
+                self.__out = miniserde::__private::Some(#ident::#var_ident);
+                miniserde::__private::Ok(miniserde::__private::None)
+            }
+        }
+    });
+    let content_arm = variants.content.iter().map(|variant| {
+        let pat = &variant.pat;
+        let cast = visitor_cast(input, quote!(&mut self.__out), &variant.visitor);
+        quote! {
+            #pat => miniserde::__private::Ok(miniserde::__private::Some(#cast)),
+        }
+    });
+    let fallback = match &variants.other {
+        Some(other) => quote! {
+            __name => {
+                self.__out = miniserde::__private::Some(#other);
+                miniserde::__private::Ok(miniserde::__private::None)
+            }
+        },
+        None => quote!(_ => miniserde::__private::Err(miniserde::Error),),
+    };
+    quote! {
+        impl #de_impl_generics miniserde::de::Visitor<'__de> for __Visitor #ty_generics #bounded_where_clause {
+            fn map<'__a>(&'__a mut self) -> miniserde::Result<miniserde::__private::Box<dyn miniserde::de::Map<'__de> + '__a>> where '__de: '__a {
+                miniserde::__private::internally_tagged(#tag, #identstr, self)
+            }
+        }
+
+        impl #de_impl_generics miniserde::__private::Variants<'__de> for __Visitor #ty_generics #bounded_where_clause {
+            fn variant(&mut self, __name: &miniserde::__private::str) -> miniserde::Result<miniserde::__private::Option<&mut dyn miniserde::de::Visitor<'__de>>> {
+                match __name {
+                    #(#unit_arm)*
+                    #(#content_arm)*
+                    #fallback
                 }
             }
-        };
-    })
+        }
+    }
+}
+
+// The visitor of an adjacently tagged enum, which is a map holding the variant
+// name under the tag key and the data of the variant under the content key.
+// Newtype data is held aside in `__Adjacent` until the map is finished.
+fn adjacently_tagged(
+    input: &DeriveInput,
+    variants: &Variants,
+    tag: &str,
+    content: &str,
+) -> TokenStream {
+    let ident = &input.ident;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let wrapper_de_generics = bound::with_de_lifetime(&wrapper_generics, &input.generics);
+    let (wrapper_de_impl_generics, _, _) = wrapper_de_generics.split_for_impl();
+    let bound = parse_quote!(miniserde::Deserialize<'__de>);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+    let identstr = ident.to_string();
+    let unit_arm = variants.unit.iter().map(|variant| {
+        let pat = &variant.pat;
+        let var_ident = variant.ident;
+        quote! {
+            #pat => {
+                *self.__out = miniserde::__private::Some(#ident::#var_ident);
+                miniserde::__private::Ok(miniserde::__private::None)
+            }
+        }
+    });
+    let newtype_arm = variants.newtype.iter().map(|variant| {
+        let pat = &variant.pat;
+        let place = &variant.place;
+        quote! {
+            #pat => miniserde::__private::Ok(miniserde::__private::Some(
+                miniserde::Deserialize::begin(&mut self.#place),
+            )),
+        }
+    });
+    let content_arm = variants.content.iter().map(|variant| {
+        let pat = &variant.pat;
+        let cast = visitor_cast(input, quote!(&mut *self.__out), &variant.visitor);
+        quote! {
+            #pat => miniserde::__private::Ok(miniserde::__private::Some(#cast)),
+        }
+    });
+    // The data of an unrecognized variant is ignored.
+    let fallback = match &variants.other {
+        Some(other) => quote! {
+            __name => {
+                *self.__out = miniserde::__private::Some(#other);
+                miniserde::__private::Ok(miniserde::__private::Some(<dyn miniserde::de::Visitor>::ignore()))
+            }
+        },
+        None => quote!(_ => miniserde::__private::Err(miniserde::Error),),
+    };
+    let ignores_data = variants.other.as_ref().map(|_| {
+        let unit_pat = variants.unit.iter().map(|variant| &variant.pat);
+        let newtype_pat = variants.newtype.iter().map(|variant| &variant.pat);
+        let content_pat = variants.content.iter().map(|variant| &variant.pat);
+        quote! {
+            fn ignores_data(&self, __name: &miniserde::__private::str) -> bool {
+                match __name {
+                    #(#unit_pat => false,)*
+                    #(#newtype_pat => false,)*
+                    #(#content_pat => false,)*
+                    _ => true,
+                }
+            }
+        }
+    });
+    let newtype_init = variants.newtype.iter().map(|variant| {
+        let place = &variant.place;
+        quote!(#place: miniserde::__private::None,)
+    });
+    let newtype_field = variants.newtype.iter().map(|variant| {
+        let place = &variant.place;
+        let ty = variant.ty;
+        quote!(#place: miniserde::__private::Option<#ty>,)
+    });
+    let newtype_finish = variants.newtype.iter().map(|variant| {
+        let place = &variant.place;
+        let var_ident = variant.ident;
+        quote! {
+            if let miniserde::__private::Some(__value) = self.#place.take() {
+                *self.__out = miniserde::__private::Some(#ident::#var_ident(__value));
+            }
+        }
+    });
+    quote! {
+        impl #de_impl_generics miniserde::de::Visitor<'__de> for __Visitor #ty_generics #bounded_where_clause {
+            fn map<'__a>(&'__a mut self) -> miniserde::Result<miniserde::__private::Box<dyn miniserde::de::Map<'__de> + '__a>> where '__de: '__a {
+                miniserde::__private::adjacently_tagged(
+                    #tag,
+                    #content,
+                    #identstr,
+                    miniserde::__private::Box::new(__Adjacent {
+                        __out: &mut self.__out,
+                        #(#newtype_init)*
+                    }),
+                )
+            }
+        }
+
+        struct __Adjacent #wrapper_impl_generics #where_clause {
+            __out: &'__a mut miniserde::__private::Option<#ident #ty_generics>,
+            #(#newtype_field)*
+        }
+
+        impl #wrapper_de_impl_generics miniserde::__private::Variants<'__de> for __Adjacent #wrapper_ty_generics #bounded_where_clause {
+            fn variant(&mut self, __name: &miniserde::__private::str) -> miniserde::Result<miniserde::__private::Option<&mut dyn miniserde::de::Visitor<'__de>>> {
+                match __name {
+                    #(#unit_arm)*
+                    #(#newtype_arm)*
+                    #(#content_arm)*
+                    #fallback
+                }
+            }
+
+            #ignores_data
+
+            fn finish(&mut self) -> miniserde::Result<()> {
+                #(#newtype_finish)*
+                miniserde::__private::Ok(())
+            }
+        }
+    }
+}
+
+// The visitor of an enum of unit variants represented by their discriminants.
+fn int_discriminant(input: &DeriveInput, variants: &Variants) -> TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let unit_ident = variants.unit.iter().map(|variant| variant.ident);
+    let fallback = match &variants.other {
+        Some(other) => quote! {
+            self.__out = miniserde::__private::Some(#other);
+            miniserde::__private::Ok(())
+        },
+        None => quote!(miniserde::__private::Err(miniserde::Error)),
+    };
+    quote! {
+        impl #impl_generics __Visitor #ty_generics #where_clause {
+            fn discriminant(&mut self, __n: i128) -> miniserde::Result<()> {
+                #(
+                    if __n == #ident::#unit_ident as i128 {
+                        self.__out = miniserde::__private::Some(#ident::#unit_ident);
+                        return miniserde::__private::Ok(());
+                    }
+                )*
+                #fallback
+            }
+        }
+
+        impl #de_impl_generics miniserde::de::Visitor<'__de> for __Visitor #ty_generics #where_clause {
+            fn negative(&mut self, __n: i64) -> miniserde::Result<()> {
+                self.discriminant(__n as i128)
+            }
+
+            fn nonnegative(&mut self, __n: u64) -> miniserde::Result<()> {
+                self.discriminant(__n as i128)
+            }
+        }
+    }
+}
+
+// One attempt of an untagged enum's visitor at deserializing the buffered
+// input as a tuple or struct variant.
+fn untagged_content(input: &DeriveInput, visitor: &Ident) -> TokenStream {
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let cast = visitor_cast(input, quote!(&mut __content), visitor);
+    quote! {
+        let mut __content: miniserde::__private::Option<#ident #ty_generics> = miniserde::__private::None;
+        let __visitor = #cast;
+        if miniserde::de::replay(&__value, __visitor).is_ok() && __content.is_some() {
+            self.__out = __content;
+            return miniserde::__private::Ok(());
+        }
+    }
+}
+
+// The Deserialize impl of a derived type, whose place to deserialize into is
+// its #[repr(C)] __Visitor.
+fn impl_begin(input: &DeriveInput, where_clause: &dyn ToTokens) -> TokenStream {
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let visitor = Ident::new("__Visitor", Span::call_site());
+    let cast = visitor_cast(input, quote!(__out), &visitor);
+    quote! {
+        impl #de_impl_generics miniserde::Deserialize<'__de> for #ident #ty_generics #where_clause {
+            fn begin(__out: &mut miniserde::__private::Option<Self>) -> &mut dyn miniserde::de::Visitor<'__de> {
+                #cast
+            }
+        }
+    }
+}
+
+// Reinterprets `out`, a &mut Option<Self>, as a &mut `visitor`. This is the
+// only unsafe code in derived impls. It is sound because every visitor passed
+// here is a #[repr(C)] struct whose only field is an Option<Self>.
+fn visitor_cast(input: &DeriveInput, out: TokenStream, visitor: &Ident) -> TokenStream {
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    quote! {
+        unsafe {
+            &mut *{
+                #out
+                as *mut miniserde::__private::Option<#ident #ty_generics>
+                as *mut #visitor #ty_generics
+            }
+        }
+    }
 }
@@ -8,6 +8,7 @@ extern crate proc_macro;
 
 mod attr;
 mod bound;
+mod case;
 mod de;
 mod ser;
 
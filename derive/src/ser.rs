@@ -2,20 +2,29 @@ use crate::{attr, bound};
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
-    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Ident, Result,
+    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed,
+    FieldsUnnamed, Ident, Result, Type,
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    if let Some(into) = attr::conversions(&input.attrs)?.into {
+        return derive_into(&input, &into);
+    }
+
     match &input.data {
         Data::Struct(DataStruct {
             fields: Fields::Named(fields),
             ..
         }) => derive_struct(&input, fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(fields),
+            ..
+        }) => derive_tuple_struct(&input, fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => derive_unit_struct(&input),
         Data::Enum(enumeration) => derive_enum(&input, enumeration),
-        Data::Struct(_) => Err(Error::new(
-            Span::call_site(),
-            "currently only structs with named fields are supported",
-        )),
         Data::Union(_) => Err(Error::new(
             Span::call_site(),
             "currently only structs and enums are supported by this derive",
@@ -23,6 +32,37 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
     }
 }
 
+// A container with #[serde(into = "...")] is cloned, converted to the other
+// type, and serialized as that.
+fn derive_into(input: &DeriveInput, into: &Type) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+
+    let mut generics = input.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates
+        .push(parse_quote!(Self: miniserde::__private::Clone + miniserde::__private::Into<#into>));
+    predicates.push(parse_quote!(#into: miniserde::Serialize));
+    let where_clause = generics.where_clause.unwrap();
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
+        const #dummy: () = {
+            impl #impl_generics miniserde::Serialize for #ident #ty_generics #where_clause {
+                fn begin(&self) -> miniserde::ser::Fragment {
+                    let __value = miniserde::__private::Clone::clone(self);
+                    let __value: #into = miniserde::__private::Into::into(__value);
+                    miniserde::__private::serialize_into(__value)
+                }
+            }
+        };
+    })
+}
+
 fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStream> {
     let ident = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
@@ -31,13 +71,170 @@ fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStrea
         Span::call_site(),
     );
 
-    let fieldname = &fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
-    let fieldstr = fields
-        .named
-        .iter()
-        .map(attr::name_of_field)
-        .collect::<Result<Vec<_>>>()?;
-    let index = 0usize..;
+    let container = attr::container(&input.attrs)?;
+    match container.tagging {
+        attr::Tagging::External => {}
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "#[serde(tag)], #[serde(content)], #[serde(untagged)] and #[serde(repr)] are only supported on enums",
+            ));
+        }
+    }
+
+    // A transparent struct is serialized as its one field.
+    if container.transparent {
+        let field = attr::transparent_field(fields)?;
+        let name = &field.ident;
+        let bound = parse_quote!(miniserde::Serialize);
+        let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+        return Ok(quote! {
+            #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
+            const #dummy: () = {
+                impl #impl_generics miniserde::Serialize for #ident #ty_generics #bounded_where_clause {
+                    fn begin(&self) -> miniserde::ser::Fragment {
+                        miniserde::Serialize::begin(&self.#name)
+                    }
+                }
+            };
+        });
+    }
+
+    let mut fieldvalue = Vec::new();
+    let mut fieldstr = Vec::new();
+    let mut skip_if = Vec::new();
+    let mut flattenname = Vec::new();
+    let mut flattenfield = Vec::new();
+    let mut entry = Vec::new();
+    let mut with = false;
+    for field in &fields.named {
+        let attrs = attr::field(field, &container)?;
+        if attrs.skip_serializing {
+            continue;
+        }
+        let name = &field.ident;
+        let next = entry.len() + 1;
+        let skip = attrs
+            .skip_serializing_if
+            .as_ref()
+            .map(|path| quote!(#path(&self.data.#name)));
+        if attrs.flatten {
+            let flatten = Ident::new(
+                &format!("__flatten_{}", name.as_ref().unwrap()),
+                Span::call_site(),
+            );
+            let forward = quote! {
+                match self.#flatten.next() {
+                    miniserde::__private::Some(__entry) => return miniserde::__private::Some(__entry),
+                    miniserde::__private::None => self.state = #next,
+                }
+            };
+            entry.push(match skip {
+                Some(skip) => quote! {
+                    if #skip {
+                        self.state = #next;
+                    } else {
+                        #forward
+                    }
+                },
+                None => forward,
+            });
+            flattenname.push(flatten);
+            flattenfield.push(name);
+            continue;
+        }
+        let key = &attrs.name;
+        let value = match &attrs.serialize_with {
+            Some(path) => {
+                with = true;
+                quote!(self.__with.set(#path(&self.data.#name)))
+            }
+            None => quote!(&self.data.#name),
+        };
+        let some = quote! {
+            miniserde::__private::Some((
+                miniserde::__private::Cow::Borrowed(#key),
+                #value,
+            ))
+        };
+        entry.push(match &skip {
+            Some(skip) => quote! {
+                self.state = #next;
+                if !#skip {
+                    return #some;
+                }
+            },
+            None => quote! {
+                self.state = #next;
+                return #some;
+            },
+        });
+        skip_if.push(skip.map(|skip| quote!(if #skip { continue; })));
+        fieldvalue.push(value);
+        fieldstr.push(attrs.name);
+    }
+
+    // Entries of a flattened field are forwarded one at a time from the
+    // field's own ser::Map, so the state of a struct with flattened fields
+    // advances by falling through a chain of conditions rather than jumping
+    // through a match. Only a field with #[serde(skip_serializing_if)] needs
+    // the match to be in a loop, to move on to the next state in place of
+    // returning an entry.
+    let next = if flattenname.is_empty() && skip_if.iter().all(Option::is_none) {
+        let index = 0usize..;
+        quote! {
+            let __state = self.state;
+            self.state = __state + 1;
+            match __state {
+                #(
+                    #index => miniserde::__private::Some((
+                        miniserde::__private::Cow::Borrowed(#fieldstr),
+                        #fieldvalue,
+                    )),
+                )*
+                _ => miniserde::__private::None,
+            }
+        }
+    } else if flattenname.is_empty() {
+        let index = 0usize..;
+        quote! {
+            loop {
+                let __state = self.state;
+                self.state = __state + 1;
+                return match __state {
+                    #(
+                        #index => {
+                            #skip_if
+                            miniserde::__private::Some((
+                                miniserde::__private::Cow::Borrowed(#fieldstr),
+                                #fieldvalue,
+                            ))
+                        }
+                    )*
+                    _ => miniserde::__private::None,
+                };
+            }
+        }
+    } else {
+        let index = 0usize..;
+        quote! {
+            #(
+                if self.state == #index {
+                    #entry
+                }
+            )*
+            miniserde::__private::None
+        }
+    };
+
+    let (with, withinit) = if with {
+        (
+            Some(quote!(__with: miniserde::__private::SerializeWith<'__a>,)),
+            Some(quote!(__with: miniserde::__private::Default::default(),)),
+        )
+    } else {
+        (None, None)
+    };
 
     let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
     let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
@@ -45,13 +242,17 @@ fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStrea
     let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
 
     Ok(quote! {
-        #[allow(non_upper_case_globals)]
+        #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
         const #dummy: () = {
             impl #impl_generics miniserde::Serialize for #ident #ty_generics #bounded_where_clause {
                 fn begin(&self) -> miniserde::ser::Fragment {
                     miniserde::ser::Fragment::Map(miniserde::__private::Box::new(__Map {
                         data: self,
                         state: 0,
+                        #withinit
+                        #(
+                            #flattenname: miniserde::__private::flatten_map(&self.#flattenfield),
+                        )*
                     }))
                 }
             }
@@ -59,18 +260,80 @@ fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStrea
             struct __Map #wrapper_impl_generics #where_clause {
                 data: &'__a #ident #ty_generics,
                 state: miniserde::__private::usize,
+                #with
+                #(
+                    #flattenname: miniserde::__private::Box<dyn miniserde::ser::Map + '__a>,
+                )*
             }
 
             impl #wrapper_impl_generics miniserde::ser::Map for __Map #wrapper_ty_generics #bounded_where_clause {
                 fn next(&mut self) -> miniserde::__private::Option<(miniserde::__private::Cow<miniserde::__private::str>, &dyn miniserde::Serialize)> {
+                    #next
+                }
+            }
+        };
+    })
+}
+
+// A tuple struct is serialized as an array, except for a newtype struct which is
+// serialized as its field.
+fn derive_tuple_struct(input: &DeriveInput, fields: &FieldsUnnamed) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+
+    attr::unnamed_struct(&input.attrs)?;
+    for field in &fields.unnamed {
+        attr::unnamed_field(field)?;
+    }
+    let bound = parse_quote!(miniserde::Serialize);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+
+    if fields.unnamed.len() == 1 {
+        return Ok(quote! {
+            #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
+            const #dummy: () = {
+                impl #impl_generics miniserde::Serialize for #ident #ty_generics #bounded_where_clause {
+                    fn begin(&self) -> miniserde::ser::Fragment {
+                        miniserde::Serialize::begin(&self.0)
+                    }
+                }
+            };
+        });
+    }
+
+    let index = (0..fields.unnamed.len()).map(syn::Index::from);
+    let state = 0usize..;
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
+        const #dummy: () = {
+            impl #impl_generics miniserde::Serialize for #ident #ty_generics #bounded_where_clause {
+                fn begin(&self) -> miniserde::ser::Fragment {
+                    miniserde::ser::Fragment::Seq(miniserde::__private::Box::new(__Seq {
+                        data: self,
+                        state: 0,
+                    }))
+                }
+            }
+
+            struct __Seq #wrapper_impl_generics #where_clause {
+                data: &'__a #ident #ty_generics,
+                state: miniserde::__private::usize,
+            }
+
+            impl #wrapper_impl_generics miniserde::ser::Seq for __Seq #wrapper_ty_generics #bounded_where_clause {
+                fn next(&mut self) -> miniserde::__private::Option<&dyn miniserde::Serialize> {
                     let __state = self.state;
                     self.state = __state + 1;
                     match __state {
                         #(
-                            #index => miniserde::__private::Some((
-                                miniserde::__private::Cow::Borrowed(#fieldstr),
-                                &self.data.#fieldname,
-                            )),
+                            #state => miniserde::__private::Some(&self.data.#index),
                         )*
                         _ => miniserde::__private::None,
                     }
@@ -80,51 +343,356 @@ fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStrea
     })
 }
 
-fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
-    if input.generics.lt_token.is_some() || input.generics.where_clause.is_some() {
-        return Err(Error::new(
-            Span::call_site(),
-            "Enums with generics are not supported",
-        ));
-    }
-
+// A unit struct is serialized as null.
+fn derive_unit_struct(input: &DeriveInput) -> Result<TokenStream> {
     let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let dummy = Ident::new(
         &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
         Span::call_site(),
     );
 
-    let var_idents = enumeration
-        .variants
-        .iter()
-        .map(|variant| match variant.fields {
-            Fields::Unit => Ok(&variant.ident),
-            _ => Err(Error::new_spanned(
-                variant,
-                "Invalid variant: only simple enum variants without fields are supported",
-            )),
-        })
-        .collect::<Result<Vec<_>>>()?;
-    let names = enumeration
-        .variants
-        .iter()
-        .map(attr::name_of_variant)
-        .collect::<Result<Vec<_>>>()?;
+    attr::unnamed_struct(&input.attrs)?;
 
     Ok(quote! {
-        #[allow(non_upper_case_globals)]
+        #[allow(non_upper_case_globals, unknown_lints, non_local_definitions)]
         const #dummy: () = {
-            impl miniserde::Serialize for #ident {
+            impl #impl_generics miniserde::Serialize for #ident #ty_generics #where_clause {
                 fn begin(&self) -> miniserde::ser::Fragment {
-                    match self {
+                    miniserde::ser::Fragment::Null
+                }
+            }
+        };
+    })
+}
+
+fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+
+    let container = attr::container(&input.attrs)?;
+    if container.transparent {
+        return Err(Error::new(
+            Span::call_site(),
+            "#[serde(transparent)] is only supported on structs",
+        ));
+    }
+    let variant_container = container.variant_fields();
+    if let attr::Tagging::Int = container.tagging {
+        for variant in &enumeration.variants {
+            if let Fields::Named(_) | Fields::Unnamed(_) = variant.fields {
+                return Err(Error::new_spanned(
+                    variant,
+                    "#[serde(repr = \"int\")] supports only unit variants",
+                ));
+            }
+        }
+    }
+
+    let is_int = match container.tagging {
+        attr::Tagging::Int => true,
+        _ => false,
+    };
+
+    // Fields with a custom serializer hand out their fragment through a slot
+    // in __Map.
+    let mut with = false;
+    for variant in &enumeration.variants {
+        if let Fields::Named(fields) = &variant.fields {
+            for field in &fields.named {
+                with |= attr::field(field, &variant_container)?
+                    .serialize_with
+                    .is_some();
+            }
+        }
+    }
+    let (with, withinit) = if with {
+        (
+            Some(quote!(__with: miniserde::__private::SerializeWith<'__a>,)),
+            Some(quote!(__with: miniserde::__private::Default::default(),)),
+        )
+    } else {
+        (None, None)
+    };
+
+    let mut arms = Vec::new();
+    let mut seq_arms = Vec::new();
+    let mut map_arms = Vec::new();
+    for variant in &enumeration.variants {
+        let attrs = attr::variant(variant, &container)?;
+        let name = &attrs.name;
+        let var_ident = &variant.ident;
+        // The #[serde(other)] newtype variant is written out as the name that
+        // it holds, the same way as a unit variant.
+        let (unit, key, tag_value) = match &variant.fields {
+            Fields::Unnamed(_) if attrs.other => (
+                Some(quote!(#ident::#var_ident(__field0))),
+                quote!(__field0),
+                quote!(__field0),
+            ),
+            Fields::Unit => (
+                Some(quote!(#ident::#var_ident)),
+                quote!(#name),
+                quote!(&#name),
+            ),
+            _ => (None, quote!(#name), quote!(&#name)),
+        };
+        // An internally tagged variant is a map whose first entry is the tag.
+        let tag_entry = match &container.tagging {
+            attr::Tagging::Internal { tag } => Some(quote! {
+                0 => miniserde::__private::Some((
+                    miniserde::__private::Cow::Borrowed(#tag),
+                    #tag_value,
+                )),
+            }),
+            _ => None,
+        };
+        let begin_map = quote! {
+            miniserde::ser::Fragment::Map(miniserde::__private::Box::new(__Map {
+                data: self,
+                state: 0,
+                #withinit
+            }))
+        };
+        match &variant.fields {
+            Fields::Unit if is_int => arms.push(quote! {
+                #ident::#var_ident => {
+                    miniserde::__private::discriminant(#ident::#var_ident as i128)
+                }
+            }),
+            _ if unit.is_some() && tag_entry.is_some() => {
+                arms.push(quote! {
+                    #unit => #begin_map,
+                });
+                map_arms.push(quote! {
+                    #unit => match __state {
+                        #tag_entry
+                        _ => miniserde::__private::None,
+                    },
+                });
+            }
+            Fields::Unit => {
+                let fragment = represent(&container.tagging, &key, None);
+                arms.push(quote! {
+                    #unit => #fragment,
+                });
+            }
+            Fields::Unnamed(fields) if attrs.other => {
+                attr::unnamed_field(&fields.unnamed[0])?;
+                let fragment = represent(&container.tagging, &key, None);
+                arms.push(quote! {
+                    #unit => #fragment,
+                });
+            }
+            Fields::Unnamed(_) if tag_entry.is_some() => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "internally tagged enums support only unit and struct variants",
+                ));
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                attr::unnamed_field(&fields.unnamed[0])?;
+                let fragment = represent(
+                    &container.tagging,
+                    &key,
+                    Some(quote!(miniserde::Serialize::begin(__field0))),
+                );
+                arms.push(quote! {
+                    #ident::#var_ident(__field0) => #fragment,
+                });
+            }
+            Fields::Unnamed(fields) => {
+                for field in &fields.unnamed {
+                    attr::unnamed_field(field)?;
+                }
+                let fieldname = (0..fields.unnamed.len())
+                    .map(|i| Ident::new(&format!("__field{}", i), Span::call_site()))
+                    .collect::<Vec<_>>();
+                let index = 0usize..;
+                let fragment = represent(
+                    &container.tagging,
+                    &key,
+                    Some(quote! {
+                        miniserde::ser::Fragment::Seq(miniserde::__private::Box::new(__Seq {
+                            data: self,
+                            state: 0,
+                        }))
+                    }),
+                );
+                arms.push(quote! {
+                    #ident::#var_ident(..) => #fragment,
+                });
+                seq_arms.push(quote! {
+                    #ident::#var_ident(#(#fieldname),*) => match __state {
                         #(
-                            #ident::#var_idents => {
-                                miniserde::ser::Fragment::Str(miniserde::__private::Cow::Borrowed(#names))
+                            #index => miniserde::__private::Some(#fieldname),
+                        )*
+                        _ => miniserde::__private::None,
+                    },
+                });
+            }
+            Fields::Named(fields) => {
+                let mut fieldident = Vec::new();
+                let mut fieldname = Vec::new();
+                let mut fieldstr = Vec::new();
+                let mut fieldvalue = Vec::new();
+                let mut skip_if = Vec::new();
+                for field in &fields.named {
+                    let attrs = attr::field(field, &variant_container)?;
+                    if attrs.flatten {
+                        return Err(Error::new_spanned(
+                            field,
+                            "#[serde(flatten)] is not supported in enum variants",
+                        ));
+                    }
+                    if attrs.skip_serializing {
+                        continue;
+                    }
+                    let name =
+                        Ident::new(&format!("__field{}", fieldname.len()), Span::call_site());
+                    skip_if.push(attrs.skip_serializing_if.map(|path| {
+                        quote! {
+                            if #path(#name) {
+                                continue;
+                            }
+                        }
+                    }));
+                    fieldvalue.push(match &attrs.serialize_with {
+                        Some(path) => quote!(self.__with.set(#path(#name))),
+                        None => quote!(#name),
+                    });
+                    fieldident.push(&field.ident);
+                    fieldname.push(name);
+                    fieldstr.push(attrs.name);
+                }
+                let index = tag_entry.iter().count()..;
+                let fragment = if tag_entry.is_some() {
+                    begin_map
+                } else {
+                    represent(&container.tagging, &key, Some(begin_map))
+                };
+                arms.push(quote! {
+                    #ident::#var_ident { .. } => #fragment,
+                });
+                map_arms.push(quote! {
+                    #ident::#var_ident { #(#fieldident: #fieldname,)* .. } => match __state {
+                        #tag_entry
+                        #(
+                            #index => {
+                                #skip_if
+                                miniserde::__private::Some((
+                                    miniserde::__private::Cow::Borrowed(#fieldstr),
+                                    #fieldvalue,
+                                ))
                             }
                         )*
+                        _ => miniserde::__private::None,
+                    },
+                });
+            }
+        }
+    }
+
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let bound = parse_quote!(miniserde::Serialize);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+
+    // The data of tuple and struct variants is streamed by walking the fields
+    // of whichever variant `data` holds.
+    let seq = if seq_arms.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            struct __Seq #wrapper_impl_generics #where_clause {
+                data: &'__a #ident #ty_generics,
+                state: miniserde::__private::usize,
+            }
+
+            impl #wrapper_impl_generics miniserde::ser::Seq for __Seq #wrapper_ty_generics #bounded_where_clause {
+                fn next(&mut self) -> miniserde::__private::Option<&dyn miniserde::Serialize> {
+                    let __state = self.state;
+                    self.state = __state + 1;
+                    match self.data {
+                        #(#seq_arms)*
+                        _ => miniserde::__private::None,
+                    }
+                }
+            }
+        })
+    };
+    let map = if map_arms.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            struct __Map #wrapper_impl_generics #where_clause {
+                data: &'__a #ident #ty_generics,
+                state: miniserde::__private::usize,
+                #with
+            }
+
+            impl #wrapper_impl_generics miniserde::ser::Map for __Map #wrapper_ty_generics #bounded_where_clause {
+                fn next(&mut self) -> miniserde::__private::Option<(miniserde::__private::Cow<miniserde::__private::str>, &dyn miniserde::Serialize)> {
+                    loop {
+                        let __state = self.state;
+                        self.state = __state + 1;
+                        return match self.data {
+                            #(#map_arms)*
+                            _ => miniserde::__private::None,
+                        };
+                    }
+                }
+            }
+        })
+    };
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals, unreachable_patterns, unknown_lints, non_local_definitions)]
+        const #dummy: () = {
+            impl #impl_generics miniserde::Serialize for #ident #ty_generics #bounded_where_clause {
+                fn begin(&self) -> miniserde::ser::Fragment {
+                    match self {
+                        #(#arms)*
                     }
                 }
             }
+
+            #seq
+            #map
         };
     })
 }
+
+// The fragment of a variant in the enum's representation, given the fragment
+// of its data if it is not a unit variant. Internally tagged variants and
+// integer discriminants are handled by the caller.
+fn represent(
+    tagging: &attr::Tagging,
+    name: &TokenStream,
+    content: Option<TokenStream>,
+) -> TokenStream {
+    match tagging {
+        attr::Tagging::External | attr::Tagging::Internal { .. } => match content {
+            Some(content) => quote!(miniserde::__private::variant(#name, #content)),
+            None => quote! {
+                miniserde::ser::Fragment::Str(miniserde::__private::Cow::Borrowed(#name))
+            },
+        },
+        attr::Tagging::Adjacent { tag, content: key } => {
+            let content = match content {
+                Some(content) => quote!(miniserde::__private::Some(#content)),
+                None => quote!(miniserde::__private::None),
+            };
+            quote!(miniserde::__private::adjacent_variant(#tag, #key, #name, #content))
+        }
+        attr::Tagging::Untagged => match content {
+            Some(content) => content,
+            None => quote!(miniserde::ser::Fragment::Null),
+        },
+        attr::Tagging::Int => unreachable!(),
+    }
+}
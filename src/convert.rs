@@ -0,0 +1,191 @@
+// Support for derived impls that deserialize some other type and then convert
+// it, such as newtype structs and #[serde(from = "...")], or that convert to
+// some other type and serialize that, as for #[serde(into = "...")].
+
+use crate::de::{self, Deserialize, Visitor};
+use crate::error::{Error, Result};
+use crate::ignore::Ignore;
+use crate::ptr::NonuniqueBox;
+use crate::ser::{self, Fragment, Serialize};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use core::mem::ManuallyDrop;
+
+// Deserialize a scalar as a `T` by handing its visitor to `visit`, then store
+// the converted value in `out`.
+pub fn convert<'de, T, U>(
+    out: &mut Option<U>,
+    f: fn(T) -> Result<U>,
+    visit: impl FnOnce(&mut dyn Visitor<'de>) -> Result<()>,
+) -> Result<()>
+where
+    T: Deserialize<'de>,
+{
+    let mut value = None;
+    visit(T::begin(&mut value))?;
+    *out = Some(f(value.ok_or(Error)?)?);
+    Ok(())
+}
+
+pub fn convert_seq<'a, 'de, T, U>(
+    out: &'a mut Option<U>,
+    f: fn(T) -> Result<U>,
+) -> Result<Box<dyn de::Seq<'de> + 'a>>
+where
+    T: Deserialize<'de> + 'a,
+    U: 'a,
+    'de: 'a,
+{
+    let mut value = NonuniqueBox::new(None);
+    let ptr = unsafe { extend_lifetime!(&mut *value as &mut Option<T>) };
+    Ok(Box::new(ConvertSeq {
+        out,
+        f,
+        value,
+        seq: ManuallyDrop::new(T::begin(ptr).seq()?),
+    }))
+}
+
+pub fn convert_map<'a, 'de, T, U>(
+    out: &'a mut Option<U>,
+    f: fn(T) -> Result<U>,
+) -> Result<Box<dyn de::Map<'de> + 'a>>
+where
+    T: Deserialize<'de> + 'a,
+    U: 'a,
+    'de: 'a,
+{
+    let mut value = NonuniqueBox::new(None);
+    let ptr = unsafe { extend_lifetime!(&mut *value as &mut Option<T>) };
+    Ok(Box::new(ConvertMap {
+        out,
+        f,
+        value,
+        map: ManuallyDrop::new(T::begin(ptr).map()?),
+    }))
+}
+
+struct ConvertSeq<'a, 'de, T: 'a, U: 'a> {
+    out: &'a mut Option<U>,
+    f: fn(T) -> Result<U>,
+    value: NonuniqueBox<Option<T>>,
+    // May borrow from self.value, so must drop first.
+    seq: ManuallyDrop<Box<dyn de::Seq<'de> + 'a>>,
+}
+
+impl<'a, 'de, T: 'a, U: 'a> Drop for ConvertSeq<'a, 'de, T, U> {
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.seq) }
+    }
+}
+
+impl<'a, 'de, T: 'a, U: 'a> de::Seq<'de> for ConvertSeq<'a, 'de, T, U> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.seq.element()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.seq.finish()?;
+        *self.seq = Box::new(Ignore);
+        let value = self.value.take().ok_or(Error)?;
+        *self.out = Some((self.f)(value)?);
+        Ok(())
+    }
+}
+
+struct ConvertMap<'a, 'de, T: 'a, U: 'a> {
+    out: &'a mut Option<U>,
+    f: fn(T) -> Result<U>,
+    value: NonuniqueBox<Option<T>>,
+    // May borrow from self.value, so must drop first.
+    map: ManuallyDrop<Box<dyn de::Map<'de> + 'a>>,
+}
+
+impl<'a, 'de, T: 'a, U: 'a> Drop for ConvertMap<'a, 'de, T, U> {
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.map) }
+    }
+}
+
+impl<'a, 'de, T: 'a, U: 'a> de::Map<'de> for ConvertMap<'a, 'de, T, U> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        self.map.key(k)
+    }
+
+    fn __flatten_key(&mut self, k: &str) -> Result<Option<&mut dyn Visitor<'de>>> {
+        self.map.__flatten_key(k)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.map.finish()?;
+        *self.map = Box::new(Ignore);
+        let value = self.value.take().ok_or(Error)?;
+        *self.out = Some((self.f)(value)?);
+        Ok(())
+    }
+}
+
+// Serialize a value that was converted from the one being serialized. The
+// result of `begin` borrows from the converted value, so a seq or map takes
+// ownership of it for as long as it is being iterated.
+pub fn serialize_into<'a, T>(value: T) -> Fragment<'a>
+where
+    T: Serialize + 'a,
+{
+    let value = NonuniqueBox::new(value);
+    let ptr = unsafe { extend_lifetime!(&*value as &T) };
+    match ptr.begin() {
+        Fragment::Null => Fragment::Null,
+        Fragment::Bool(b) => Fragment::Bool(b),
+        Fragment::Str(s) => Fragment::Str(Cow::Owned(s.into_owned())),
+        Fragment::U64(n) => Fragment::U64(n),
+        Fragment::I64(n) => Fragment::I64(n),
+        Fragment::F64(n) => Fragment::F64(n),
+        Fragment::Seq(seq) => Fragment::Seq(Box::new(IntoSeq {
+            value,
+            seq: ManuallyDrop::new(seq),
+        })),
+        Fragment::Map(map) => Fragment::Map(Box::new(IntoMap {
+            value,
+            map: ManuallyDrop::new(map),
+        })),
+    }
+}
+
+struct IntoSeq<'a, T: 'a> {
+    #[allow(dead_code)]
+    value: NonuniqueBox<T>,
+    // May borrow from self.value, so must drop first.
+    seq: ManuallyDrop<Box<dyn ser::Seq + 'a>>,
+}
+
+impl<'a, T: 'a> Drop for IntoSeq<'a, T> {
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.seq) }
+    }
+}
+
+impl<'a, T: 'a> ser::Seq for IntoSeq<'a, T> {
+    fn next(&mut self) -> Option<&dyn Serialize> {
+        self.seq.next()
+    }
+}
+
+struct IntoMap<'a, T: 'a> {
+    #[allow(dead_code)]
+    value: NonuniqueBox<T>,
+    // May borrow from self.value, so must drop first.
+    map: ManuallyDrop<Box<dyn ser::Map + 'a>>,
+}
+
+impl<'a, T: 'a> Drop for IntoMap<'a, T> {
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.map) }
+    }
+}
+
+impl<'a, T: 'a> ser::Map for IntoMap<'a, T> {
+    fn next(&mut self) -> Option<(Cow<str>, &dyn Serialize)> {
+        self.map.next()
+    }
+}
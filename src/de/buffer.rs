@@ -0,0 +1,114 @@
+use crate::de::{Buffered, Deserialize, Map, Seq, Visitor};
+use crate::error::Result;
+use crate::json::{Array, Number, Object, Value};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::mem;
+
+impl<'de, T: Buffered> Visitor<'de> for T {
+    fn null(&mut self) -> Result<()> {
+        self.buffered(Value::Null)
+    }
+
+    fn boolean(&mut self, b: bool) -> Result<()> {
+        self.buffered(Value::Bool(b))
+    }
+
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.buffered(Value::String(s.to_owned()))
+    }
+
+    fn negative(&mut self, n: i64) -> Result<()> {
+        self.buffered(Value::Number(Number::I64(n)))
+    }
+
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        self.buffered(Value::Number(Number::U64(n)))
+    }
+
+    fn float(&mut self, n: f64) -> Result<()> {
+        self.buffered(Value::Number(Number::F64(n)))
+    }
+
+    fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+    where
+        'de: 'a,
+    {
+        Ok(Box::new(ArrayBuffer {
+            out: self,
+            array: Array::new(),
+            element: None,
+        }))
+    }
+
+    fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+    where
+        'de: 'a,
+    {
+        Ok(Box::new(ObjectBuffer {
+            out: self,
+            object: Object::new(),
+            key: None,
+            value: None,
+        }))
+    }
+}
+
+// Like the builders behind `Deserialize for Value`, but handing the finished
+// value to a Buffered impl rather than writing it to a place.
+struct ArrayBuffer<'a> {
+    out: &'a mut dyn Buffered,
+    array: Array,
+    element: Option<Value>,
+}
+
+impl<'a> ArrayBuffer<'a> {
+    fn shift(&mut self) {
+        if let Some(e) = self.element.take() {
+            self.array.push(e);
+        }
+    }
+}
+
+impl<'a, 'de> Seq<'de> for ArrayBuffer<'a> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        Ok(Deserialize::begin(&mut self.element))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        let array = mem::replace(&mut self.array, Array::new());
+        self.out.buffered(Value::Array(array))
+    }
+}
+
+struct ObjectBuffer<'a> {
+    out: &'a mut dyn Buffered,
+    object: Object,
+    key: Option<String>,
+    value: Option<Value>,
+}
+
+impl<'a> ObjectBuffer<'a> {
+    fn shift(&mut self) {
+        if let (Some(k), Some(v)) = (self.key.take(), self.value.take()) {
+            self.object.insert(k, v);
+        }
+    }
+}
+
+impl<'a, 'de> Map<'de> for ObjectBuffer<'a> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        self.key = Some(k.to_owned());
+        Ok(Deserialize::begin(&mut self.value))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        let object = mem::replace(&mut self.object, Object::new());
+        self.out.buffered(Value::Object(object))
+    }
+}
@@ -0,0 +1,146 @@
+use crate::de::{Deserialize, Map, Seq, Visitor};
+use crate::error::Result;
+use crate::json::Number;
+use crate::Place;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+// A value held aside by a derived impl until it knows where the value goes,
+// such as the fields of an internally tagged enum that come before the tag.
+// Unlike `json::Value`, strings borrowed from the input stay borrowed, so that
+// replaying them can still produce a `&'de str`.
+pub(crate) enum Content<'de> {
+    Null,
+    Bool(bool),
+    Number(Number),
+    Str(&'de str),
+    String(String),
+    Seq(Vec<Content<'de>>),
+    Map(BTreeMap<String, Content<'de>>),
+}
+
+impl<'de> Deserialize<'de> for Content<'de> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<Content<'de>> {
+            fn null(&mut self) -> Result<()> {
+                self.out = Some(Content::Null);
+                Ok(())
+            }
+
+            fn boolean(&mut self, b: bool) -> Result<()> {
+                self.out = Some(Content::Bool(b));
+                Ok(())
+            }
+
+            fn string(&mut self, s: &str) -> Result<()> {
+                self.out = Some(Content::String(s.to_owned()));
+                Ok(())
+            }
+
+            fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+                self.out = Some(Content::Str(s));
+                Ok(())
+            }
+
+            fn negative(&mut self, n: i64) -> Result<()> {
+                self.out = Some(Content::Number(Number::I64(n)));
+                Ok(())
+            }
+
+            fn nonnegative(&mut self, n: u64) -> Result<()> {
+                self.out = Some(Content::Number(Number::U64(n)));
+                Ok(())
+            }
+
+            fn float(&mut self, n: f64) -> Result<()> {
+                self.out = Some(Content::Number(Number::F64(n)));
+                Ok(())
+            }
+
+            fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+            where
+                'de: 'a,
+            {
+                Ok(Box::new(SeqBuilder {
+                    out: &mut self.out,
+                    seq: Vec::new(),
+                    element: None,
+                }))
+            }
+
+            fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+            where
+                'de: 'a,
+            {
+                Ok(Box::new(MapBuilder {
+                    out: &mut self.out,
+                    map: BTreeMap::new(),
+                    key: None,
+                    value: None,
+                }))
+            }
+        }
+
+        struct SeqBuilder<'a, 'de> {
+            out: &'a mut Option<Content<'de>>,
+            seq: Vec<Content<'de>>,
+            element: Option<Content<'de>>,
+        }
+
+        impl<'a, 'de> SeqBuilder<'a, 'de> {
+            fn shift(&mut self) {
+                if let Some(e) = self.element.take() {
+                    self.seq.push(e);
+                }
+            }
+        }
+
+        impl<'a, 'de> Seq<'de> for SeqBuilder<'a, 'de> {
+            fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+                self.shift();
+                Ok(Deserialize::begin(&mut self.element))
+            }
+
+            fn finish(&mut self) -> Result<()> {
+                self.shift();
+                *self.out = Some(Content::Seq(mem::replace(&mut self.seq, Vec::new())));
+                Ok(())
+            }
+        }
+
+        struct MapBuilder<'a, 'de> {
+            out: &'a mut Option<Content<'de>>,
+            map: BTreeMap<String, Content<'de>>,
+            key: Option<String>,
+            value: Option<Content<'de>>,
+        }
+
+        impl<'a, 'de> MapBuilder<'a, 'de> {
+            fn shift(&mut self) {
+                if let (Some(k), Some(v)) = (self.key.take(), self.value.take()) {
+                    self.map.insert(k, v);
+                }
+            }
+        }
+
+        impl<'a, 'de> Map<'de> for MapBuilder<'a, 'de> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+                self.shift();
+                self.key = Some(k.to_owned());
+                Ok(Deserialize::begin(&mut self.value))
+            }
+
+            fn finish(&mut self) -> Result<()> {
+                self.shift();
+                *self.out = Some(Content::Map(mem::replace(&mut self.map, BTreeMap::new())));
+                Ok(())
+            }
+        }
+
+        Place::new(out)
+    }
+}
@@ -3,7 +3,7 @@ use crate::error::{Error, Result};
 use crate::ignore::Ignore;
 use crate::ptr::NonuniqueBox;
 use crate::Place;
-use alloc::borrow::ToOwned;
+use alloc::borrow::{Cow, ToOwned};
 use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
 use alloc::string::String;
@@ -15,9 +15,9 @@ use std::collections::HashMap;
 #[cfg(feature = "std")]
 use std::hash::{BuildHasher, Hash};
 
-impl Deserialize for () {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<()> {
+impl<'de> Deserialize<'de> for () {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<()> {
             fn null(&mut self) -> Result<()> {
                 self.out = Some(());
                 Ok(())
@@ -27,9 +27,9 @@ impl Deserialize for () {
     }
 }
 
-impl Deserialize for bool {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<bool> {
+impl<'de> Deserialize<'de> for bool {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<bool> {
             fn boolean(&mut self, b: bool) -> Result<()> {
                 self.out = Some(b);
                 Ok(())
@@ -39,9 +39,9 @@ impl Deserialize for bool {
     }
 }
 
-impl Deserialize for String {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<String> {
+impl<'de> Deserialize<'de> for String {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<String> {
             fn string(&mut self, s: &str) -> Result<()> {
                 self.out = Some(s.to_owned());
                 Ok(())
@@ -51,11 +51,40 @@ impl Deserialize for String {
     }
 }
 
+impl<'de: 'a, 'a> Deserialize<'de> for &'a str {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de: 'a, 'a> Visitor<'de> for Place<&'a str> {
+            fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+                self.out = Some(s);
+                Ok(())
+            }
+        }
+        Place::new(out)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Cow<'a, str> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de: 'a, 'a> Visitor<'de> for Place<Cow<'a, str>> {
+            fn string(&mut self, s: &str) -> Result<()> {
+                self.out = Some(Cow::Owned(s.to_owned()));
+                Ok(())
+            }
+
+            fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+                self.out = Some(Cow::Borrowed(s));
+                Ok(())
+            }
+        }
+        Place::new(out)
+    }
+}
+
 macro_rules! signed {
     ($ty:ident) => {
-        impl Deserialize for $ty {
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-                impl Visitor for Place<$ty> {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                impl<'de> Visitor<'de> for Place<$ty> {
                     fn negative(&mut self, n: i64) -> Result<()> {
                         if n >= $ty::min_value() as i64 {
                             self.out = Some(n as $ty);
@@ -87,9 +116,9 @@ signed!(isize);
 
 macro_rules! unsigned {
     ($ty:ident) => {
-        impl Deserialize for $ty {
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-                impl Visitor for Place<$ty> {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                impl<'de> Visitor<'de> for Place<$ty> {
                     fn nonnegative(&mut self, n: u64) -> Result<()> {
                         if n <= $ty::max_value() as u64 {
                             self.out = Some(n as $ty);
@@ -112,9 +141,9 @@ unsigned!(usize);
 
 macro_rules! float {
     ($ty:ident) => {
-        impl Deserialize for $ty {
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-                impl Visitor for Place<$ty> {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                impl<'de> Visitor<'de> for Place<$ty> {
                     fn negative(&mut self, n: i64) -> Result<()> {
                         self.out = Some(n as $ty);
                         Ok(())
@@ -138,9 +167,9 @@ macro_rules! float {
 float!(f32);
 float!(f64);
 
-impl<T: Deserialize> Deserialize for Box<T> {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl<T: Deserialize> Visitor for Place<Box<T>> {
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Box<T> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<Box<T>> {
             fn null(&mut self) -> Result<()> {
                 let mut out = None;
                 Deserialize::begin(&mut out).null()?;
@@ -162,6 +191,13 @@ impl<T: Deserialize> Deserialize for Box<T> {
                 Ok(())
             }
 
+            fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+                let mut out = None;
+                Deserialize::begin(&mut out).borrowed_string(s)?;
+                self.out = Some(Box::new(out.unwrap()));
+                Ok(())
+            }
+
             fn negative(&mut self, n: i64) -> Result<()> {
                 let mut out = None;
                 Deserialize::begin(&mut out).negative(n)?;
@@ -183,7 +219,10 @@ impl<T: Deserialize> Deserialize for Box<T> {
                 Ok(())
             }
 
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+            fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+            where
+                'de: 'a,
+            {
                 let mut value = NonuniqueBox::new(None);
                 let ptr = unsafe { extend_lifetime!(&mut *value as &mut Option<T>) };
                 Ok(Box::new(BoxSeq {
@@ -193,7 +232,10 @@ impl<T: Deserialize> Deserialize for Box<T> {
                 }))
             }
 
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+            fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+            where
+                'de: 'a,
+            {
                 let mut value = NonuniqueBox::new(None);
                 let ptr = unsafe { extend_lifetime!(&mut *value as &mut Option<T>) };
                 Ok(Box::new(BoxMap {
@@ -204,21 +246,21 @@ impl<T: Deserialize> Deserialize for Box<T> {
             }
         }
 
-        struct BoxSeq<'a, T: 'a> {
+        struct BoxSeq<'a, 'de, T: 'a> {
             out: &'a mut Option<Box<T>>,
             value: NonuniqueBox<Option<T>>,
             // May borrow from self.value, so must drop first.
-            seq: ManuallyDrop<Box<dyn Seq + 'a>>,
+            seq: ManuallyDrop<Box<dyn Seq<'de> + 'a>>,
         }
 
-        impl<'a, T: 'a> Drop for BoxSeq<'a, T> {
+        impl<'a, 'de, T: 'a> Drop for BoxSeq<'a, 'de, T> {
             fn drop(&mut self) {
                 unsafe { ManuallyDrop::drop(&mut self.seq) }
             }
         }
 
-        impl<'a, T: Deserialize> Seq for BoxSeq<'a, T> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, T: Deserialize<'de>> Seq<'de> for BoxSeq<'a, 'de, T> {
+            fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
                 self.seq.element()
             }
 
@@ -230,24 +272,28 @@ impl<T: Deserialize> Deserialize for Box<T> {
             }
         }
 
-        struct BoxMap<'a, T: 'a> {
+        struct BoxMap<'a, 'de, T: 'a> {
             out: &'a mut Option<Box<T>>,
             value: NonuniqueBox<Option<T>>,
             // May borrow from self.value, so must drop first.
-            map: ManuallyDrop<Box<dyn Map + 'a>>,
+            map: ManuallyDrop<Box<dyn Map<'de> + 'a>>,
         }
 
-        impl<'a, T: 'a> Drop for BoxMap<'a, T> {
+        impl<'a, 'de, T: 'a> Drop for BoxMap<'a, 'de, T> {
             fn drop(&mut self) {
                 unsafe { ManuallyDrop::drop(&mut self.map) }
             }
         }
 
-        impl<'a, T: Deserialize> Map for BoxMap<'a, T> {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, T: Deserialize<'de>> Map<'de> for BoxMap<'a, 'de, T> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
                 self.map.key(k)
             }
 
+            fn __flatten_key(&mut self, k: &str) -> Result<Option<&mut dyn Visitor<'de>>> {
+                self.map.__flatten_key(k)
+            }
+
             fn finish(&mut self) -> Result<()> {
                 self.map.finish()?;
                 *self.map = Box::new(Ignore);
@@ -260,13 +306,13 @@ impl<T: Deserialize> Deserialize for Box<T> {
     }
 }
 
-impl<T: Deserialize> Deserialize for Option<T> {
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Option<T> {
     #[inline]
     fn default() -> Option<Self> {
         Some(None)
     }
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl<T: Deserialize> Visitor for Place<Option<T>> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<Option<T>> {
             fn null(&mut self) -> Result<()> {
                 self.out = Some(None);
                 Ok(())
@@ -282,6 +328,11 @@ impl<T: Deserialize> Deserialize for Option<T> {
                 Deserialize::begin(self.out.as_mut().unwrap()).string(s)
             }
 
+            fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+                self.out = Some(None);
+                Deserialize::begin(self.out.as_mut().unwrap()).borrowed_string(s)
+            }
+
             fn negative(&mut self, n: i64) -> Result<()> {
                 self.out = Some(None);
                 Deserialize::begin(self.out.as_mut().unwrap()).negative(n)
@@ -297,12 +348,18 @@ impl<T: Deserialize> Deserialize for Option<T> {
                 Deserialize::begin(self.out.as_mut().unwrap()).float(n)
             }
 
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+            fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+            where
+                'de: 'a,
+            {
                 self.out = Some(None);
                 Deserialize::begin(self.out.as_mut().unwrap()).seq()
             }
 
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+            fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+            where
+                'de: 'a,
+            {
                 self.out = Some(None);
                 Deserialize::begin(self.out.as_mut().unwrap()).map()
             }
@@ -312,10 +369,13 @@ impl<T: Deserialize> Deserialize for Option<T> {
     }
 }
 
-impl<A: Deserialize, B: Deserialize> Deserialize for (A, B) {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl<A: Deserialize, B: Deserialize> Visitor for Place<(A, B)> {
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+impl<'de, A: Deserialize<'de>, B: Deserialize<'de>> Deserialize<'de> for (A, B) {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de, A: Deserialize<'de>, B: Deserialize<'de>> Visitor<'de> for Place<(A, B)> {
+            fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+            where
+                'de: 'a,
+            {
                 Ok(Box::new(TupleBuilder {
                     out: &mut self.out,
                     tuple: (None, None),
@@ -328,8 +388,8 @@ impl<A: Deserialize, B: Deserialize> Deserialize for (A, B) {
             tuple: (Option<A>, Option<B>),
         }
 
-        impl<'a, A: Deserialize, B: Deserialize> Seq for TupleBuilder<'a, A, B> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, A: Deserialize<'de>, B: Deserialize<'de>> Seq<'de> for TupleBuilder<'a, A, B> {
+            fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
                 if self.tuple.0.is_none() {
                     Ok(Deserialize::begin(&mut self.tuple.0))
                 } else if self.tuple.1.is_none() {
@@ -353,10 +413,13 @@ impl<A: Deserialize, B: Deserialize> Deserialize for (A, B) {
     }
 }
 
-impl<T: Deserialize> Deserialize for Vec<T> {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl<T: Deserialize> Visitor for Place<Vec<T>> {
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Vec<T> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<Vec<T>> {
+            fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+            where
+                'de: 'a,
+            {
                 Ok(Box::new(VecBuilder {
                     out: &mut self.out,
                     vec: Vec::new(),
@@ -379,8 +442,8 @@ impl<T: Deserialize> Deserialize for Vec<T> {
             }
         }
 
-        impl<'a, T: Deserialize> Seq for VecBuilder<'a, T> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, T: Deserialize<'de>> Seq<'de> for VecBuilder<'a, T> {
+            fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
                 self.shift();
                 Ok(Deserialize::begin(&mut self.element))
             }
@@ -397,20 +460,23 @@ impl<T: Deserialize> Deserialize for Vec<T> {
 }
 
 #[cfg(feature = "std")]
-impl<K, V, H> Deserialize for HashMap<K, V, H>
+impl<'de, K, V, H> Deserialize<'de> for HashMap<K, V, H>
 where
     K: FromStr + Hash + Eq,
-    V: Deserialize,
+    V: Deserialize<'de>,
     H: BuildHasher + Default,
 {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl<K, V, H> Visitor for Place<HashMap<K, V, H>>
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de, K, V, H> Visitor<'de> for Place<HashMap<K, V, H>>
         where
             K: FromStr + Hash + Eq,
-            V: Deserialize,
+            V: Deserialize<'de>,
             H: BuildHasher + Default,
         {
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+            fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+            where
+                'de: 'a,
+            {
                 Ok(Box::new(MapBuilder {
                     out: &mut self.out,
                     map: HashMap::with_hasher(H::default()),
@@ -435,13 +501,13 @@ where
             }
         }
 
-        impl<'a, K, V, H> Map for MapBuilder<'a, K, V, H>
+        impl<'a, 'de, K, V, H> Map<'de> for MapBuilder<'a, K, V, H>
         where
             K: FromStr + Hash + Eq,
-            V: Deserialize,
+            V: Deserialize<'de>,
             H: BuildHasher + Default,
         {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
                 self.shift();
                 self.key = Some(match K::from_str(k) {
                     Ok(key) => key,
@@ -462,10 +528,13 @@ where
     }
 }
 
-impl<K: FromStr + Ord, V: Deserialize> Deserialize for BTreeMap<K, V> {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl<K: FromStr + Ord, V: Deserialize> Visitor for Place<BTreeMap<K, V>> {
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+impl<'de, K: FromStr + Ord, V: Deserialize<'de>> Deserialize<'de> for BTreeMap<K, V> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de, K: FromStr + Ord, V: Deserialize<'de>> Visitor<'de> for Place<BTreeMap<K, V>> {
+            fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+            where
+                'de: 'a,
+            {
                 Ok(Box::new(MapBuilder {
                     out: &mut self.out,
                     map: BTreeMap::new(),
@@ -490,8 +559,8 @@ impl<K: FromStr + Ord, V: Deserialize> Deserialize for BTreeMap<K, V> {
             }
         }
 
-        impl<'a, K: FromStr + Ord, V: Deserialize> Map for MapBuilder<'a, K, V> {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, K: FromStr + Ord, V: Deserialize<'de>> Map<'de> for MapBuilder<'a, K, V> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
                 self.shift();
                 self.key = Some(match K::from_str(k) {
                     Ok(key) => key,
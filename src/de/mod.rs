@@ -37,15 +37,15 @@
 //! // output place.
 //! //
 //! // These methods may perform validation and decide to return an error.
-//! impl Visitor for Place<MyBoolean> {
+//! impl<'de> Visitor<'de> for Place<MyBoolean> {
 //!     fn boolean(&mut self, b: bool) -> Result<()> {
 //!         self.out = Some(MyBoolean(b));
 //!         Ok(())
 //!     }
 //! }
 //!
-//! impl Deserialize for MyBoolean {
-//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+//! impl<'de> Deserialize<'de> for MyBoolean {
+//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
 //!         // All Deserialize impls will look exactly like this. There is no
 //!         // other correct implementation of Deserialize.
 //!         Place::new(out)
@@ -67,8 +67,11 @@
 //!
 //! struct MyVec<T>(Vec<T>);
 //!
-//! impl<T: Deserialize> Visitor for Place<MyVec<T>> {
-//!     fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+//! impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<MyVec<T>> {
+//!     fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+//!     where
+//!         'de: 'a,
+//!     {
 //!         Ok(Box::new(VecBuilder {
 //!             out: &mut self.out,
 //!             vec: Vec::new(),
@@ -86,8 +89,8 @@
 //!     element: Option<T>,
 //! }
 //!
-//! impl<'a, T: Deserialize> Seq for VecBuilder<'a, T> {
-//!     fn element(&mut self) -> Result<&mut dyn Visitor> {
+//! impl<'a, 'de, T: Deserialize<'de>> Seq<'de> for VecBuilder<'a, T> {
+//!     fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
 //!         // Free up the place by transfering the most recent element
 //!         // into self.vec.
 //!         self.vec.extend(self.element.take());
@@ -105,8 +108,8 @@
 //!     }
 //! }
 //!
-//! impl<T: Deserialize> Deserialize for MyVec<T> {
-//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+//! impl<'de, T: Deserialize<'de>> Deserialize<'de> for MyVec<T> {
+//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
 //!         // As mentioned, all Deserialize impls will look like this.
 //!         Place::new(out)
 //!     }
@@ -119,7 +122,7 @@
 //! `#[derive(Deserialize)]`.
 //!
 //! ```rust
-//! use miniserde::{make_place, Result};
+//! use miniserde::{make_place, Error, Result};
 //! use miniserde::de::{Deserialize, Map, Visitor};
 //!
 //! make_place!(Place);
@@ -130,8 +133,11 @@
 //!     message: String,
 //! }
 //!
-//! impl Visitor for Place<Demo> {
-//!     fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+//! impl<'de> Visitor<'de> for Place<Demo> {
+//!     fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+//!     where
+//!         'de: 'a,
+//!     {
 //!         // Like for sequences, we produce a builder that can hand out places
 //!         // to write one struct field at a time.
 //!         Ok(Box::new(DemoBuilder {
@@ -148,8 +154,8 @@
 //!     out: &'a mut Option<Demo>,
 //! }
 //!
-//! impl<'a> Map for DemoBuilder<'a> {
-//!     fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+//! impl<'a, 'de> Map<'de> for DemoBuilder<'a> {
+//!     fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
 //!         // Figure out which field is being deserialized and return a place
 //!         // to write it.
 //!         //
@@ -160,37 +166,90 @@
 //!         match k {
 //!             "code" => Ok(Deserialize::begin(&mut self.code)),
 //!             "message" => Ok(Deserialize::begin(&mut self.message)),
-//!             _ => Ok(<dyn Visitor>::ignore()),
+//!             _ => Ok(<dyn Visitor<'de>>::ignore()),
 //!         }
 //!     }
 //!
 //!     fn finish(&mut self) -> Result<()> {
 //!         // Make sure we have every field and then write the output object
 //!         // into self.out.
-//!         let code = self.code.take().ok_or(miniserde::Error)?;
-//!         let message = self.message.take().ok_or(miniserde::Error)?;
+//!         let code = self.code.take().ok_or_else(|| Error::missing_field("code", "Demo"))?;
+//!         let message = self.message.take().ok_or_else(|| Error::missing_field("message", "Demo"))?;
 //!         *self.out = Some(Demo { code, message });
 //!         Ok(())
 //!     }
 //! }
 //!
-//! impl Deserialize for Demo {
-//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+//! impl<'de> Deserialize<'de> for Demo {
+//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
 //!         // All Deserialize impls look like this.
 //!         Place::new(out)
 //!     }
 //! }
 //! ```
+//!
+//! ## Borrowing from the input
+//!
+//! The `'de` lifetime is the lifetime of the input being deserialized. A
+//! string that appears in the input with no escape sequences is handed to
+//! `Visitor::borrowed_string` for as long as the input lives, which lets a type
+//! hold on to it without copying. Every other string goes to
+//! `Visitor::string`, which is also where `borrowed_string` forwards unless
+//! overridden.
+//!
+//! A string can only be borrowed if it exists verbatim in the input, so a type
+//! that accepts nothing but `borrowed_string`, such as `&str`, fails on:
+//!
+//! - strings containing escape sequences;
+//! - any string read through `json::from_reader`, which never borrows;
+//! - strings that pass through a [`Buffered`] visitor, including derived
+//!   `#[serde(untagged)]` enums, because a `json::Value` owns its strings.
+//!
+//! Use `Cow<str>` or `String` in those places. Fields that are held aside by
+//! `#[serde(flatten)]` or by an internally or adjacently tagged enum until the
+//! tag has been read do keep borrowing from the input.
+//!
+//! ```rust
+//! use miniserde::{make_place, Result};
+//! use miniserde::de::{Deserialize, Visitor};
+//!
+//! make_place!(Place);
+//!
+//! struct Name<'a>(&'a str);
+//!
+//! // Deserializable from any input that outlives the name.
+//! impl<'de: 'a, 'a> Visitor<'de> for Place<Name<'a>> {
+//!     fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+//!         self.out = Some(Name(s));
+//!         Ok(())
+//!     }
+//! }
+//!
+//! impl<'de: 'a, 'a> Deserialize<'de> for Name<'a> {
+//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+//!         Place::new(out)
+//!     }
+//! }
+//! ```
 
+mod buffer;
+pub(crate) mod content;
 mod impls;
+pub(crate) mod replay;
+
+pub use self::replay::replay;
 
 use crate::error::{Error, Result};
+use crate::json::Value;
 use alloc::boxed::Box;
 
 /// Trait for data structures that can be deserialized from a JSON string.
 ///
+/// The lifetime `'de` is that of the input, which the data structure may
+/// borrow strings from.
+///
 /// [Refer to the module documentation for examples.][crate::de]
-pub trait Deserialize: Sized {
+pub trait Deserialize<'de>: Sized {
     /// The only correct implementation of this method is:
     ///
     /// ```rust
@@ -199,15 +258,15 @@ pub trait Deserialize: Sized {
     /// #
     /// # make_place!(Place);
     /// # struct S;
-    /// # impl Visitor for Place<S> {}
+    /// # impl<'de> Visitor<'de> for Place<S> {}
     /// #
-    /// # impl Deserialize for S {
-    /// fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+    /// # impl<'de> Deserialize<'de> for S {
+    /// fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
     ///     Place::new(out)
     /// }
     /// # }
     /// ```
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor;
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de>;
 
     // Not public API. This method is only intended for Option<T>, should not
     // need to be implemented outside of this crate.
@@ -218,10 +277,19 @@ pub trait Deserialize: Sized {
     }
 }
 
+/// Data structures that can be deserialized from input of any lifetime, because
+/// they do not borrow from it.
+///
+/// This is what [`json::from_reader`][crate::json::from_reader] requires,
+/// since input read from an `io::Read` does not stay around to be borrowed.
+pub trait DeserializeOwned: for<'de> Deserialize<'de> {}
+
+impl<T> DeserializeOwned for T where T: for<'de> Deserialize<'de> {}
+
 /// Trait that can write data into an output place.
 ///
 /// [Refer to the module documentation for examples.][crate::de]
-pub trait Visitor {
+pub trait Visitor<'de> {
     fn null(&mut self) -> Result<()> {
         Err(Error)
     }
@@ -236,6 +304,12 @@ pub trait Visitor {
         Err(Error)
     }
 
+    /// A string that can be borrowed from the input for the lifetime `'de`.
+    /// Forwards to `string` unless overridden.
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.string(s)
+    }
+
     fn negative(&mut self, n: i64) -> Result<()> {
         let _ = n;
         Err(Error)
@@ -251,11 +325,21 @@ pub trait Visitor {
         Err(Error)
     }
 
-    fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+    /// Begin deserializing a sequence. The `'de: 'a` bound allows the builder
+    /// to hold on to visitors of the same input, such as to delegate to the
+    /// `seq` of another type.
+    fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+    where
+        'de: 'a,
+    {
         Err(Error)
     }
 
-    fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+    /// Begin deserializing a map, with the same `'de: 'a` bound as `seq`.
+    fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+    where
+        'de: 'a,
+    {
         Err(Error)
     }
 }
@@ -263,15 +347,77 @@ pub trait Visitor {
 /// Trait that can hand out places to write sequence elements.
 ///
 /// [Refer to the module documentation for examples.][crate::de]
-pub trait Seq {
-    fn element(&mut self) -> Result<&mut dyn Visitor>;
+pub trait Seq<'de> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>>;
     fn finish(&mut self) -> Result<()>;
 }
 
 /// Trait that can hand out places to write values of a map.
 ///
 /// [Refer to the module documentation for examples.][crate::de]
-pub trait Map {
-    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor>;
+pub trait Map<'de> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>>;
     fn finish(&mut self) -> Result<()>;
+
+    // Not public API. Like `key`, but None for a key that the map has no use
+    // for, so that #[serde(flatten)] can leave it to the fields that come
+    // after. Overridden by derived impls and by maps that forward to another.
+    #[doc(hidden)]
+    fn __flatten_key(&mut self, k: &str) -> Result<Option<&mut dyn Visitor<'de>>> {
+        self.key(k).map(Some)
+    }
+}
+
+/// Trait for visitors that need to see a complete value before they can decide
+/// how to deserialize it.
+///
+/// Every type that implements `Buffered` is also a [`Visitor`] which accepts
+/// any input at all, collects it into a [`json::Value`][crate::json::Value],
+/// and hands the finished value to [`buffered`][Buffered::buffered]. From
+/// there the value can be fed through [`replay`] into as many other visitors as
+/// needed, for example to try a series of candidate types in turn.
+///
+/// ```rust
+/// use miniserde::{json, make_place, Error, Result};
+/// use miniserde::de::{self, Buffered, Deserialize, Visitor};
+/// use miniserde::json::Value;
+///
+/// make_place!(Place);
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Id {
+///     Number(u64),
+///     Name(String),
+/// }
+///
+/// impl Buffered for Place<Id> {
+///     fn buffered(&mut self, value: Value) -> Result<()> {
+///         let mut number = None;
+///         if de::replay(&value, u64::begin(&mut number)).is_ok() {
+///             self.out = number.map(Id::Number);
+///             return Ok(());
+///         }
+///         let mut name = None;
+///         if de::replay(&value, String::begin(&mut name)).is_ok() {
+///             self.out = name.map(Id::Name);
+///             return Ok(());
+///         }
+///         Err(Error)
+///     }
+/// }
+///
+/// impl<'de> Deserialize<'de> for Id {
+///     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+///         Place::new(out)
+///     }
+/// }
+///
+/// fn main() -> miniserde::Result<()> {
+///     let ids: Vec<Id> = json::from_str(r#"[1, "two"]"#)?;
+///     assert_eq!(ids, [Id::Number(1), Id::Name("two".to_owned())]);
+///     Ok(())
+/// }
+/// ```
+pub trait Buffered {
+    fn buffered(&mut self, value: Value) -> Result<()>;
 }
@@ -0,0 +1,165 @@
+use crate::de::content::Content;
+use crate::de::{Map, Seq, Visitor};
+use crate::error::Result;
+use crate::json::{Number, Value};
+use crate::ptr::NonuniqueBox;
+use alloc::collections::{btree_map, BTreeMap};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ptr::NonNull;
+use core::slice;
+
+/// Feed a buffered value into a visitor, as though it were being deserialized
+/// from the original input.
+///
+/// Like the JSON deserializer, this does not recurse, so arbitrarily nested
+/// values can be replayed. [Refer to `Buffered` for an example.][super::Buffered]
+pub fn replay<'de>(value: &Value, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    replay_value(value, visitor)
+}
+
+// Replay any kind of buffered value into a visitor.
+pub(crate) fn replay_value<'de, V: Replay<'de>>(
+    value: &V,
+    visitor: &mut dyn Visitor<'de>,
+) -> Result<()> {
+    match value.visit(visitor)? {
+        Some(layer) => drive(layer),
+        None => Ok(()),
+    }
+}
+
+// Replay the entries of a buffered object into a visitor as a map.
+pub(crate) fn replay_map<'de, V: Replay<'de>>(
+    object: &BTreeMap<String, V>,
+    visitor: &mut dyn Visitor<'de>,
+) -> Result<()> {
+    let map = visitor.map()?;
+    drive(Layer::Map(NonuniqueBox::from(map), object.iter()))
+}
+
+// A buffered value which can be fed into a visitor one level at a time. A
+// `json::Value` only holds owned strings, while `Content` also keeps the ones
+// borrowed from the input so that they reach `Visitor::borrowed_string` again.
+pub(crate) trait Replay<'de>: Sized {
+    fn visit<'a, 'b>(
+        &'a self,
+        visitor: &'b mut dyn Visitor<'de>,
+    ) -> Result<Option<Layer<'a, 'b, 'de, Self>>>;
+}
+
+struct Replayer<'a, 'b, 'de, V> {
+    stack: Vec<Layer<'a, 'b, 'de, V>>,
+}
+
+pub(crate) enum Layer<'a, 'b, 'de, V> {
+    Seq(NonuniqueBox<dyn Seq<'de> + 'b>, slice::Iter<'a, V>),
+    Map(
+        NonuniqueBox<dyn Map<'de> + 'b>,
+        btree_map::Iter<'a, String, V>,
+    ),
+}
+
+impl<'a, 'b, 'de, V> Drop for Replayer<'a, 'b, 'de, V> {
+    fn drop(&mut self) {
+        // Drop layers in reverse order.
+        while !self.stack.is_empty() {
+            self.stack.pop();
+        }
+    }
+}
+
+impl<'de> Replay<'de> for Value {
+    fn visit<'a, 'b>(
+        &'a self,
+        visitor: &'b mut dyn Visitor<'de>,
+    ) -> Result<Option<Layer<'a, 'b, 'de, Self>>> {
+        match self {
+            Value::Null => visitor.null()?,
+            Value::Bool(b) => visitor.boolean(*b)?,
+            Value::Number(n) => visit_number(n, visitor)?,
+            Value::String(s) => visitor.string(s)?,
+            Value::Array(array) => {
+                let seq = visitor.seq()?;
+                return Ok(Some(Layer::Seq(NonuniqueBox::from(seq), array.iter())));
+            }
+            Value::Object(object) => {
+                let map = visitor.map()?;
+                return Ok(Some(Layer::Map(NonuniqueBox::from(map), object.iter())));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'de> Replay<'de> for Content<'de> {
+    fn visit<'a, 'b>(
+        &'a self,
+        visitor: &'b mut dyn Visitor<'de>,
+    ) -> Result<Option<Layer<'a, 'b, 'de, Self>>> {
+        match self {
+            Content::Null => visitor.null()?,
+            Content::Bool(b) => visitor.boolean(*b)?,
+            Content::Number(n) => visit_number(n, visitor)?,
+            Content::Str(s) => visitor.borrowed_string(s)?,
+            Content::String(s) => visitor.string(s)?,
+            Content::Seq(seq) => {
+                let visitor = visitor.seq()?;
+                return Ok(Some(Layer::Seq(NonuniqueBox::from(visitor), seq.iter())));
+            }
+            Content::Map(map) => {
+                let visitor = visitor.map()?;
+                return Ok(Some(Layer::Map(NonuniqueBox::from(visitor), map.iter())));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn visit_number<'de>(n: &Number, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    match n {
+        Number::U64(n) => visitor.nonnegative(*n),
+        Number::I64(n) => visitor.negative(*n),
+        Number::F64(n) => visitor.float(*n),
+    }
+}
+
+fn drive<'de, V: Replay<'de>>(layer: Layer<'_, '_, 'de, V>) -> Result<()> {
+    let mut replayer = Replayer { stack: Vec::new() };
+    // Declared after the replayer so that on early return the innermost layer
+    // is dropped before the layers it borrows from.
+    let mut layer = layer;
+
+    loop {
+        let next = match &mut layer {
+            Layer::Seq(seq, iter) => match iter.next() {
+                Some(value) => Some((NonNull::from(seq.element()?), value)),
+                None => {
+                    seq.finish()?;
+                    None
+                }
+            },
+            Layer::Map(map, iter) => match iter.next() {
+                Some((key, value)) => Some((NonNull::from(map.key(key)?), value)),
+                None => {
+                    map.finish()?;
+                    None
+                }
+            },
+        };
+
+        match next {
+            Some((visitor, value)) => {
+                let visitor = unsafe { extend_lifetime!(visitor as NonNull<dyn Visitor<'de>>) };
+                if let Some(child) = value.visit(unsafe { &mut *visitor.as_ptr() })? {
+                    replayer.stack.push(layer);
+                    layer = child;
+                }
+            }
+            None => match replayer.stack.pop() {
+                Some(parent) => layer = parent,
+                None => return Ok(()),
+            },
+        }
+    }
+}
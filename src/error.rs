@@ -1,18 +1,332 @@
-use core::fmt::{self, Display};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use core::fmt::{self, Debug, Display};
 
 /// Error type when deserialization fails.
 ///
-/// Miniserde errors contain no information about what went wrong. **If you need
-/// more than no information, use Serde.**
-#[derive(Copy, Clone, Debug)]
-pub struct Error;
+/// Miniserde errors carry only as much information as is cheap to produce:
+/// what [kind][ErrorKind] of failure occurred and, for errors coming out of
+/// `json::from_str` and the other JSON entry points, the position in the input
+/// at which it was detected and the [path][Error::path] to the offending value.
+/// Where they apply, an error also names the [field][Error::field] and the
+/// [container][Error::container] involved, or carries the message of a failed
+/// conversion. All of this is computed only after a failure, so none of it
+/// costs anything along the successful path.
+///
+/// If you need more than that, use Serde.
+///
+/// An `Error` with no further information is available as the constant
+/// `miniserde::Error`, which is what `Visitor`, `Seq` and `Map` impls usually
+/// return to reject the input.
+#[derive(Clone)]
+pub struct Error {
+    inner: Option<Box<ErrorImpl>>,
+}
+
+/// An error carrying no information beyond the fact that the data was
+/// rejected. Its kind is [`ErrorKind::Invalid`].
+#[allow(non_upper_case_globals)]
+pub const Error: Error = Error { inner: None };
 
 /// Result type returned by deserialization functions.
 pub type Result<T> = core::result::Result<T, Error>;
 
+/// Broad category of a deserialization [`Error`][struct@Error].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The input is not syntactically valid JSON.
+    Syntax,
+    /// The input ended in the middle of a JSON value.
+    Eof,
+    /// The input was valid JSON but a `Visitor`, `Seq` or `Map` rejected it,
+    /// for example a string where a number was expected.
+    Invalid,
+    /// A struct was missing one of its required fields.
+    MissingField,
+    /// A struct that denies unknown fields received a key it does not know.
+    UnknownField,
+    /// Reading the input from an `io::Read` failed.
+    Io,
+    /// Arrays and objects were nested deeper than
+    /// [`Limits::max_depth`][crate::json::Limits::max_depth] allows.
+    TooDeep,
+    /// The input was longer than
+    /// [`Limits::max_input_len`][crate::json::Limits::max_input_len] allows.
+    InputTooLong,
+    /// A string was longer than
+    /// [`Limits::max_str_len`][crate::json::Limits::max_str_len] allows.
+    StringTooLong,
+    /// An array or object had more elements than
+    /// [`Limits::max_elements`][crate::json::Limits::max_elements] allows.
+    TooManyElements,
+    /// The input held more values than
+    /// [`Limits::max_values`][crate::json::Limits::max_values] allows.
+    TooManyValues,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+#[derive(Clone)]
+struct ErrorImpl {
+    kind: ErrorKind,
+    position: Option<Position>,
+    path: Option<String>,
+    field: Option<Cow<'static, str>>,
+    container: Option<&'static str>,
+    message: Option<String>,
+}
+
+impl ErrorImpl {
+    fn new(kind: ErrorKind) -> Box<Self> {
+        Box::new(ErrorImpl {
+            kind,
+            position: None,
+            path: None,
+            field: None,
+            container: None,
+            message: None,
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Error {
+    /// Error for a struct `container` which did not receive a value for its
+    /// required field `field`. For a tuple struct the field is the index of
+    /// the missing element, such as `"1"`.
+    ///
+    /// ```rust
+    /// # use miniserde::{Error, ErrorKind};
+    /// #
+    /// let err = Error::missing_field("timeout_ms", "ServerConfig");
+    /// assert_eq!(err.kind(), ErrorKind::MissingField);
+    /// assert_eq!(err.to_string(), "missing field `timeout_ms` in `ServerConfig`");
+    /// ```
+    #[cold]
+    pub fn missing_field(field: &'static str, container: &'static str) -> Self {
+        let mut err = Error::from(ErrorKind::MissingField);
+        let inner = err.inner_mut();
+        inner.field = Some(Cow::Borrowed(field));
+        inner.container = Some(container);
+        err
+    }
+
+    /// Error for a struct `container` which does not accept the key `field`.
+    ///
+    /// ```rust
+    /// # use miniserde::{Error, ErrorKind};
+    /// #
+    /// let err = Error::unknown_field("timout_ms", "ServerConfig");
+    /// assert_eq!(err.kind(), ErrorKind::UnknownField);
+    /// assert_eq!(err.to_string(), "unknown field `timout_ms` in `ServerConfig`");
+    /// ```
+    #[cold]
+    pub fn unknown_field(field: &str, container: &'static str) -> Self {
+        let mut err = Error::from(ErrorKind::UnknownField);
+        let inner = err.inner_mut();
+        inner.field = Some(Cow::Owned(field.to_owned()));
+        inner.container = Some(container);
+        err
+    }
+
+    /// Error for a container `container` whose conversion from the
+    /// deserialized data, as by `#[serde(try_from = "...")]`, failed with
+    /// `msg`.
+    ///
+    /// ```rust
+    /// # use miniserde::{Error, ErrorKind};
+    /// #
+    /// let err = Error::invalid_value("missing @", "Email");
+    /// assert_eq!(err.kind(), ErrorKind::Invalid);
+    /// assert_eq!(err.to_string(), "invalid type or value in `Email`: missing @");
+    /// ```
+    #[cold]
+    pub fn invalid_value<T: Display>(msg: T, container: &'static str) -> Self {
+        let mut err = Error::from(ErrorKind::Invalid);
+        let inner = err.inner_mut();
+        inner.container = Some(container);
+        inner.message = Some(msg.to_string());
+        err
+    }
+
+    /// The category of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match &self.inner {
+            Some(inner) => inner.kind,
+            None => ErrorKind::Invalid,
+        }
+    }
+
+    /// Byte offset into the input at which the error was detected.
+    pub fn offset(&self) -> Option<usize> {
+        self.position().map(|position| position.offset)
+    }
+
+    /// One-based line number at which the error was detected.
+    pub fn line(&self) -> Option<usize> {
+        self.position().map(|position| position.line)
+    }
+
+    /// Column at which the error was detected, as the number of bytes of that
+    /// line consumed before the failure. The first byte of a line is column 1.
+    pub fn column(&self) -> Option<usize> {
+        self.position().map(|position| position.column)
+    }
+
+    /// Name of the field involved in a [`MissingField`][ErrorKind::MissingField]
+    /// or [`UnknownField`][ErrorKind::UnknownField] error.
+    pub fn field(&self) -> Option<&str> {
+        let inner = self.inner.as_ref()?;
+        inner.field.as_ref().map(|field| &**field)
+    }
+
+    /// Name of the struct involved in a
+    /// [`MissingField`][ErrorKind::MissingField] or
+    /// [`UnknownField`][ErrorKind::UnknownField] error, or of the container
+    /// whose conversion failed in an [`invalid_value`][Error::invalid_value]
+    /// error.
+    pub fn container(&self) -> Option<&str> {
+        self.inner.as_ref().and_then(|inner| inner.container)
+    }
+
+    /// Location of the offending value within the document, such as
+    /// `.users[3].address.zip`. The top-level value is `.`.
+    pub fn path(&self) -> Option<&str> {
+        self.inner
+            .as_ref()
+            .and_then(|inner| inner.path.as_ref().map(String::as_str))
+    }
+
+    fn position(&self) -> Option<Position> {
+        self.inner.as_ref().and_then(|inner| inner.position)
+    }
+
+    fn inner_mut(&mut self) -> &mut ErrorImpl {
+        self.inner
+            .get_or_insert_with(|| ErrorImpl::new(ErrorKind::Invalid))
+    }
+
+    // Record where in `input` the error was detected, unless a position is
+    // already known. A syntax error is reported as premature end of input if
+    // `eof`, meaning the input ran out while more of it was being looked for.
+    #[cold]
+    pub(crate) fn at(self, input: &[u8], offset: usize, eof: bool) -> Self {
+        let offset = offset.min(input.len());
+        let consumed = &input[..offset];
+        let line_start = match consumed.iter().rposition(|&b| b == b'\n') {
+            Some(newline) => newline + 1,
+            None => 0,
+        };
+        let line = 1 + consumed.iter().filter(|&&b| b == b'\n').count();
+        self.at_position(offset, line, offset - line_start, eof)
+    }
+
+    // Like `at`, for input that is no longer around to compute the line and
+    // column from.
+    #[cold]
+    pub(crate) fn at_position(
+        mut self,
+        offset: usize,
+        line: usize,
+        column: usize,
+        eof: bool,
+    ) -> Self {
+        let inner = self.inner_mut();
+        if inner.position.is_some() {
+            return self;
+        }
+        if inner.kind == ErrorKind::Syntax && eof {
+            inner.kind = ErrorKind::Eof;
+        }
+        inner.position = Some(Position {
+            offset,
+            line,
+            column,
+        });
+        self
+    }
+
+    // Record the location of the offending value, unless already known.
+    #[cold]
+    pub(crate) fn with_path(mut self, path: String) -> Self {
+        let inner = self.inner_mut();
+        if inner.path.is_none() {
+            inner.path = Some(path);
+        }
+        self
+    }
+}
+
+impl From<ErrorKind> for Error {
+    #[cold]
+    fn from(kind: ErrorKind) -> Self {
+        Error {
+            inner: Some(ErrorImpl::new(kind)),
+        }
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("miniserde error")
+        match self.path() {
+            Some(".") | None => {}
+            Some(path) => write!(formatter, "{}: ", path)?,
+        }
+        formatter.write_str(match self.kind() {
+            ErrorKind::Syntax => "invalid JSON syntax",
+            ErrorKind::Eof => "unexpected end of input",
+            ErrorKind::Invalid => "invalid type or value",
+            ErrorKind::MissingField => "missing field",
+            ErrorKind::UnknownField => "unknown field",
+            ErrorKind::Io => "failed to read input",
+            ErrorKind::TooDeep => "nesting too deep",
+            ErrorKind::InputTooLong => "input too long",
+            ErrorKind::StringTooLong => "string too long",
+            ErrorKind::TooManyElements => "too many elements",
+            ErrorKind::TooManyValues => "too many values",
+            ErrorKind::__NonExhaustive => "miniserde error",
+        })?;
+        if let Some(inner) = &self.inner {
+            if let Some(field) = &inner.field {
+                write!(formatter, " `{}`", field)?;
+            }
+            if let Some(container) = inner.container {
+                write!(formatter, " in `{}`", container)?;
+            }
+            if let Some(message) = &inner.message {
+                write!(formatter, ": {}", message)?;
+            }
+        }
+        if let Some(position) = self.position() {
+            write!(
+                formatter,
+                " at line {} column {}",
+                position.line, position.column,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = formatter.debug_struct("Error");
+        debug.field("kind", &self.kind());
+        if let Some(path) = self.path() {
+            debug.field("path", &path);
+        }
+        if let Some(position) = self.position() {
+            debug.field("line", &position.line);
+            debug.field("column", &position.column);
+        }
+        debug.finish()
     }
 }
 
@@ -1,9 +1,19 @@
 pub use alloc::borrow::Cow;
 pub use alloc::boxed::Box;
 pub use alloc::string::String;
+pub use core::clone::Clone;
+pub use core::convert::{From, Into, TryFrom};
+pub use core::default::Default;
 pub use core::option::Option::{self, None, Some};
 pub use core::result::Result::{Err, Ok};
 
+pub use crate::convert::{convert, convert_map, convert_seq, serialize_into};
+pub use crate::flatten::{flatten_map, FlattenBuffer};
+pub use crate::variant::{
+    adjacent_variant, adjacently_tagged, discriminant, internally_tagged, variant, Variants,
+};
+pub use crate::with::SerializeWith;
+
 pub use self::help::Str as str;
 pub use self::help::Usize as usize;
 
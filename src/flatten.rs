@@ -0,0 +1,113 @@
+// Support for #[serde(flatten)] in derived impls.
+
+use crate::de::content::Content;
+use crate::de::{self, Deserialize, Visitor};
+use crate::error::{Error, Result};
+use crate::ser::{self, Fragment, Serialize};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// Entries of a flattened field, which the derived Serialize impl emits inline
+// among the fields of the containing struct. A value that does not serialize
+// as a map, such as a None, contributes no entries.
+pub fn flatten_map(value: &dyn Serialize) -> Box<dyn ser::Map + '_> {
+    match value.begin() {
+        Fragment::Map(map) => map,
+        _ => Box::new(Empty),
+    }
+}
+
+struct Empty;
+
+impl ser::Map for Empty {
+    fn next(&mut self) -> Option<(Cow<str>, &dyn Serialize)> {
+        None
+    }
+}
+
+// Keys that the derived Deserialize impl did not recognize as one of its own
+// fields. Once the containing map is finished, these are replayed into each
+// flattened field in turn. Keys consumed by one flattened field are not offered
+// to the ones after it, so a trailing map catches whatever is left over. Like
+// in Serde, a flattened Option is None rather than an error if the buffered
+// keys do not make up its value, which is how a None serialized by
+// `flatten_map` comes back.
+#[derive(Default)]
+pub struct FlattenBuffer<'de> {
+    object: BTreeMap<String, Content<'de>>,
+    key: Option<String>,
+    value: Option<Content<'de>>,
+}
+
+impl<'de> FlattenBuffer<'de> {
+    fn shift(&mut self) {
+        if let (Some(k), Some(v)) = (self.key.take(), self.value.take()) {
+            self.object.insert(k, v);
+        }
+    }
+
+    pub fn key(&mut self, k: &str) -> &mut dyn Visitor<'de> {
+        self.shift();
+        self.key = Some(k.to_owned());
+        Deserialize::begin(&mut self.value)
+    }
+
+    pub fn deserialize<T: Deserialize<'de>>(&mut self) -> Result<T> {
+        self.shift();
+        let mut out = None;
+        let mut claimed = Vec::new();
+        let mut visitor = Claim {
+            visitor: T::begin(&mut out),
+            claimed: &mut claimed,
+        };
+        if let Err(err) = de::replay::replay_map(&self.object, &mut visitor) {
+            return T::default().ok_or(err);
+        }
+        for key in claimed {
+            self.object.remove(&key);
+        }
+        out.ok_or(Error)
+    }
+}
+
+// Records which keys a flattened field has a use for.
+struct Claim<'a, 'de> {
+    visitor: &'a mut dyn Visitor<'de>,
+    claimed: &'a mut Vec<String>,
+}
+
+struct ClaimMap<'a, 'de> {
+    map: Box<dyn de::Map<'de> + 'a>,
+    claimed: &'a mut Vec<String>,
+}
+
+impl<'a, 'de> Visitor<'de> for Claim<'a, 'de> {
+    fn map<'b>(&'b mut self) -> Result<Box<dyn de::Map<'de> + 'b>>
+    where
+        'de: 'b,
+    {
+        Ok(Box::new(ClaimMap {
+            map: self.visitor.map()?,
+            claimed: self.claimed,
+        }))
+    }
+}
+
+impl<'a, 'de> de::Map<'de> for ClaimMap<'a, 'de> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        match self.map.__flatten_key(k)? {
+            Some(visitor) => {
+                self.claimed.push(k.to_owned());
+                Ok(visitor)
+            }
+            None => Ok(<dyn Visitor>::ignore()),
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.map.finish()
+    }
+}
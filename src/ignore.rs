@@ -2,8 +2,9 @@ use crate::de::{Map, Seq, Visitor};
 use crate::error::Result;
 use alloc::boxed::Box;
 
-impl dyn Visitor {
-    pub fn ignore() -> &'static mut dyn Visitor {
+impl<'de> dyn Visitor<'de> {
+    #[allow(static_mut_refs)]
+    pub fn ignore<'a>() -> &'a mut dyn Visitor<'de> {
         static mut IGNORE: Ignore = Ignore;
         unsafe { &mut IGNORE }
         //
@@ -21,7 +22,7 @@ impl dyn Visitor {
 
 pub(crate) struct Ignore;
 
-impl Visitor for Ignore {
+impl<'de> Visitor<'de> for Ignore {
     fn null(&mut self) -> Result<()> {
         Ok(())
     }
@@ -46,17 +47,23 @@ impl Visitor for Ignore {
         Ok(())
     }
 
-    fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+    fn seq<'a>(&'a mut self) -> Result<Box<dyn Seq<'de> + 'a>>
+    where
+        'de: 'a,
+    {
         Ok(Box::new(Ignore))
     }
 
-    fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+    fn map<'a>(&'a mut self) -> Result<Box<dyn Map<'de> + 'a>>
+    where
+        'de: 'a,
+    {
         Ok(Box::new(Ignore))
     }
 }
 
-impl Seq for Ignore {
-    fn element(&mut self) -> Result<&mut dyn Visitor> {
+impl<'de> Seq<'de> for Ignore {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
         Ok(<dyn Visitor>::ignore())
     }
 
@@ -65,12 +72,16 @@ impl Seq for Ignore {
     }
 }
 
-impl Map for Ignore {
-    fn key(&mut self, _k: &str) -> Result<&mut dyn Visitor> {
+impl<'de> Map<'de> for Ignore {
+    fn key(&mut self, _k: &str) -> Result<&mut dyn Visitor<'de>> {
         Ok(<dyn Visitor>::ignore())
     }
 
     fn finish(&mut self) -> Result<()> {
         Ok(())
     }
+
+    fn __flatten_key(&mut self, _k: &str) -> Result<Option<&mut dyn Visitor<'de>>> {
+        Ok(None)
+    }
 }
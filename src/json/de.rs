@@ -1,11 +1,19 @@
 use self::Event::*;
+use super::read::{syntax, Read, Reference, SliceRead};
+use super::Limits;
+#[cfg(feature = "std")]
+use crate::de::DeserializeOwned;
 use crate::de::{Deserialize, Map, Seq, Visitor};
-use crate::error::{Error, Result};
+use crate::error::{Error, ErrorKind, Result};
 use crate::ptr::NonuniqueBox;
+use alloc::string::String;
 use alloc::vec::Vec;
-use core::char;
 use core::ptr::NonNull;
-use core::str;
+
+#[cfg(feature = "std")]
+use super::read::IoRead;
+#[cfg(feature = "std")]
+use std::io;
 
 /// Deserialize a JSON string into any deserializable type.
 ///
@@ -27,26 +35,243 @@ use core::str;
 ///     Ok(())
 /// }
 /// ```
-pub fn from_str<T: Deserialize>(j: &str) -> Result<T> {
+pub fn from_str<'de, T: Deserialize<'de>>(j: &'de str) -> Result<T> {
+    let mut out = None;
+    from_impl(SliceRead::utf8(j), &Limits::new(), T::begin(&mut out))?;
+    out.ok_or(Error)
+}
+
+/// Deserialize JSON bytes into any deserializable type.
+///
+/// Unlike `from_str`, this does not require the whole input to be valid UTF-8
+/// up front. Only the contents of string literals are checked, as they are
+/// parsed.
+///
+/// ```rust
+/// use miniserde::{json, Deserialize};
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Example {
+///     code: u32,
+///     message: String,
+/// }
+///
+/// fn main() -> miniserde::Result<()> {
+///     let j = br#" {"code": 200, "message": "reminiscent of Serde"} "#;
+///
+///     let out: Example = json::from_slice(j)?;
+///     println!("{:?}", out);
+///
+///     Ok(())
+/// }
+/// ```
+pub fn from_slice<'de, T: Deserialize<'de>>(j: &'de [u8]) -> Result<T> {
+    let mut out = None;
+    from_impl(SliceRead::new(j), &Limits::new(), T::begin(&mut out))?;
+    out.ok_or(Error)
+}
+
+/// Deserialize JSON from an `io::Read` into any deserializable type.
+///
+/// The input is parsed as it is read, through a fixed-size internal buffer,
+/// so it never needs to be held in memory all at once. The reader is not
+/// buffered any further, so wrapping a file or socket in a `BufReader` is
+/// unnecessary.
+///
+/// A failure to read is reported as an error of kind
+/// [`ErrorKind::Io`][crate::ErrorKind::Io].
+///
+/// ```rust
+/// use miniserde::{json, Deserialize};
+/// use std::fs::File;
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Example {
+///     code: u32,
+///     message: String,
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     # let dir = std::env::temp_dir().join("miniserde-from-reader");
+///     # std::fs::create_dir_all(&dir)?;
+///     # let path = dir.join("example.json");
+///     # std::fs::write(&path, r#"{"code": 200, "message": "reminiscent of Serde"}"#)?;
+///     let file = File::open(path)?;
+///
+///     let out: Example = json::from_reader(file)?;
+///     println!("{:?}", out);
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn from_reader<R: io::Read, T: DeserializeOwned>(reader: R) -> Result<T> {
     let mut out = None;
-    from_str_impl(j, T::begin(&mut out))?;
+    from_impl(IoRead::new(reader), &Limits::new(), T::begin(&mut out))?;
     out.ok_or(Error)
 }
 
-struct Deserializer<'a, 'b> {
-    input: &'a [u8],
-    pos: usize,
+pub(super) struct Deserializer<'de, R: Read<'de>> {
+    read: R,
     buffer: Vec<u8>,
-    stack: Vec<(NonNull<dyn Visitor>, Layer<'b>)>,
+    stack: Vec<Frame<'de, R::Key>>,
+    limits: Limits,
+    // Number of values begun so far, for limits.max_values.
+    values: usize,
 }
 
-enum Layer<'a> {
-    Seq(NonuniqueBox<dyn Seq + 'a>),
-    Map(NonuniqueBox<dyn Map + 'a>),
+// A builder on the stack, along with the visitor it was begun from.
+type Frame<'de, K> = (NonNull<dyn Visitor<'de>>, Layer<'de, K>);
+
+// Alongside each builder, the layer records the number of elements begun so
+// far, and for a Map whatever the input needs in order to produce the current
+// key again for the sake of error messages.
+enum Layer<'de, K> {
+    Seq(NonuniqueBox<dyn Seq<'de> + 'de>, usize),
+    Map(NonuniqueBox<dyn Map<'de> + 'de>, usize, K),
 }
 
-impl<'a, 'b> Drop for Deserializer<'a, 'b> {
+impl<'de, R: Read<'de>> Drop for Deserializer<'de, R> {
     fn drop(&mut self) {
+        self.unwind();
+    }
+}
+
+pub(super) fn from_impl<'de, R: Read<'de>>(
+    read: R,
+    limits: &Limits,
+    visitor: &mut dyn Visitor<'de>,
+) -> Result<()> {
+    let mut de = Deserializer::new(read, limits);
+    de.value(visitor)?;
+    match de.parse_whitespace() {
+        Some(_) => Err(de.fail(syntax())),
+        None if de.read.truncated() => Err(de.fail(syntax())),
+        None => Ok(()),
+    }
+}
+
+impl<'de, R: Read<'de>> Deserializer<'de, R> {
+    pub(super) fn new(mut read: R, limits: &Limits) -> Self {
+        read.limit(limits.max_input_len, limits.max_str_len);
+        Deserializer {
+            read,
+            buffer: Vec::new(),
+            stack: Vec::new(),
+            limits: *limits,
+            values: 0,
+        }
+    }
+
+    // Deserialize one value into `visitor`, leaving the input positioned right
+    // after it.
+    pub(super) fn value(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+        // The builders on the stack borrow from the output of this one value.
+        // If a visitor panics, they are dropped on the way out rather than
+        // left for a later value to finish through a dangling output.
+        struct Unwind<'a, 'de, R: Read<'de>>(&'a mut Deserializer<'de, R>);
+
+        impl<'a, 'de, R: Read<'de>> Drop for Unwind<'a, 'de, R> {
+            fn drop(&mut self) {
+                self.0.unwind();
+            }
+        }
+
+        let guard = Unwind(self);
+        match deserialize(guard.0, visitor) {
+            Ok(()) => Ok(()),
+            Err(err) => Err(guard.0.fail(err)),
+        }
+    }
+
+    // Attach the position and path to an error, and drop whatever was in the
+    // middle of being built.
+    #[cold]
+    fn fail(&mut self, err: Error) -> Error {
+        let err = self.read.error(err);
+        let path = self.path();
+        self.unwind();
+        err.with_path(path)
+    }
+
+    // Skip whitespace ahead of the next value in a stream, returning its
+    // offset and first byte, or None at the end of the input.
+    pub(super) fn begin_value(&mut self) -> Option<(usize, u8)> {
+        // Each value of a stream gets the whole of max_values to itself.
+        self.values = 0;
+        let first = self.parse_whitespace()?;
+        Some((self.read.offset(), first))
+    }
+
+    // A value in a stream must be followed by whitespace or the end of the
+    // input, unless it is an array, object or string whose end is apparent
+    // from its closing delimiter.
+    pub(super) fn end_value(&mut self, first: u8) -> Result<()> {
+        match first {
+            b'[' | b'{' | b'"' => return Ok(()),
+            _ => {}
+        }
+        match self.peek() {
+            None | Some(b' ') | Some(b'\n') | Some(b'\t') | Some(b'\r') => Ok(()),
+            Some(b'[') | Some(b'{') | Some(b'"') => Ok(()),
+            Some(_) => Err(self.fail(syntax())),
+        }
+    }
+
+    // Find where a stream of newline-delimited values should carry on after a
+    // malformed one that began at `offset`. If the error was detected on the
+    // line where the value began, that line is discarded. If it was detected
+    // on a later line, likely because the value was cut short and the next
+    // one is what tripped it up, the stream goes back to the start of that
+    // line.
+    pub(super) fn skip_line(&mut self, offset: usize) {
+        if self.read.rewind_line(offset) {
+            return;
+        }
+        while let Some(b) = self.next() {
+            if b == b'\n' {
+                break;
+            }
+        }
+    }
+
+    // Attach only the position to an error, for a caller that keeps track of
+    // the path by itself.
+    #[cold]
+    pub(super) fn error(&mut self, err: Error) -> Error {
+        self.read.error(err)
+    }
+
+    // Parse an object key whose opening quote has been consumed, recording in
+    // `key` what is needed to render it again into a path by `path_of`.
+    pub(super) fn parse_key(&mut self, key: &mut R::Key) -> Result<Reference<'de, '_>> {
+        self.read.begin_key(key);
+        let k = self.read.parse_str(&mut self.buffer)?;
+        R::end_key(key, &k);
+        Ok(k)
+    }
+
+    // Render a path for a caller that keeps track of the nesting by itself.
+    #[cold]
+    pub(super) fn path_of<'k, I>(&mut self, segments: I) -> String
+    where
+        I: IntoIterator<Item = Segment<'k, R::Key>>,
+        R::Key: 'k,
+    {
+        render_path(&self.read, &mut self.buffer, segments)
+    }
+
+    pub(super) fn offset(&self) -> usize {
+        self.read.offset()
+    }
+
+    // Whether the input ended early because of a failure to read or because it
+    // exceeded the length limit.
+    pub(super) fn truncated(&self) -> bool {
+        self.read.truncated()
+    }
+
+    fn unwind(&mut self) {
         // Drop layers in reverse order.
         while !self.stack.is_empty() {
             self.stack.pop();
@@ -54,15 +279,12 @@ impl<'a, 'b> Drop for Deserializer<'a, 'b> {
     }
 }
 
-fn from_str_impl(j: &str, visitor: &mut dyn Visitor) -> Result<()> {
+fn deserialize<'de, R: Read<'de>>(
+    de: &mut Deserializer<'de, R>,
+    visitor: &mut dyn Visitor<'de>,
+) -> Result<()> {
     let visitor = NonNull::from(visitor);
-    let mut visitor = unsafe { extend_lifetime!(visitor as NonNull<dyn Visitor>) };
-    let mut de = Deserializer {
-        input: j.as_bytes(),
-        pos: 0,
-        buffer: Vec::new(),
-        stack: Vec::new(),
-    };
+    let mut visitor = unsafe { extend_lifetime!(visitor as NonNull<dyn Visitor<'de>>) };
 
     'outer: loop {
         let visitor_mut = unsafe { &mut *visitor.as_ptr() };
@@ -87,17 +309,21 @@ fn from_str_impl(j: &str, visitor: &mut dyn Visitor) -> Result<()> {
                 visitor_mut.float(n)?;
                 None
             }
-            Str(s) => {
+            Str(Reference::Borrowed(s)) => {
+                visitor_mut.borrowed_string(s)?;
+                None
+            }
+            Str(Reference::Copied(s)) => {
                 visitor_mut.string(s)?;
                 None
             }
             SeqStart => {
                 let seq = visitor_mut.seq()?;
-                Some(Layer::Seq(NonuniqueBox::from(seq)))
+                Some(Layer::Seq(NonuniqueBox::from(seq), 0))
             }
             MapStart => {
                 let map = visitor_mut.map()?;
-                Some(Layer::Map(NonuniqueBox::from(map)))
+                Some(Layer::Map(NonuniqueBox::from(map), 0, R::Key::default()))
             }
         };
 
@@ -124,12 +350,17 @@ fn from_str_impl(j: &str, visitor: &mut dyn Visitor) -> Result<()> {
                     break;
                 }
                 close @ b']' | close @ b'}' => {
-                    de.bump();
                     match &mut layer {
-                        Layer::Seq(seq) if close == b']' => seq.finish()?,
-                        Layer::Map(map) if close == b'}' => map.finish()?,
-                        _ => return Err(Error),
-                    };
+                        Layer::Seq(seq, _) if close == b']' => {
+                            de.bump();
+                            seq.finish()?;
+                        }
+                        Layer::Map(map, _, _) if close == b'}' => {
+                            de.bump();
+                            map.finish()?;
+                        }
+                        _ => return Err(syntax()),
+                    }
                     let frame = match de.stack.pop() {
                         Some(frame) => frame,
                         None => break 'outer,
@@ -140,7 +371,7 @@ fn from_str_impl(j: &str, visitor: &mut dyn Visitor) -> Result<()> {
                 }
                 _ => {
                     if accept_comma {
-                        return Err(Error);
+                        return Err(syntax());
                     } else {
                         break;
                     }
@@ -150,40 +381,96 @@ fn from_str_impl(j: &str, visitor: &mut dyn Visitor) -> Result<()> {
 
         let outer = visitor;
         match layer {
-            Layer::Seq(mut seq) => {
-                let element = seq.element()?;
+            Layer::Seq(mut seq, len) => {
+                if len >= de.limits.max_elements {
+                    de.stack.push((outer, Layer::Seq(seq, len + 1)));
+                    return Err(Error::from(ErrorKind::TooManyElements));
+                }
+                let element = match seq.element() {
+                    Ok(element) => element,
+                    Err(err) => {
+                        de.stack.push((outer, Layer::Seq(seq, len + 1)));
+                        return Err(err);
+                    }
+                };
                 let next = NonNull::from(element);
-                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor>) };
-                de.stack.push((outer, Layer::Seq(seq)));
+                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor<'de>>) };
+                de.stack.push((outer, Layer::Seq(seq, len + 1)));
             }
-            Layer::Map(mut map) => {
+            Layer::Map(mut map, len, mut key) => {
                 match de.parse_whitespace() {
                     Some(b'"') => de.bump(),
-                    _ => return Err(Error),
+                    _ => return Err(syntax()),
                 }
-                let key = de.parse_str()?;
-                let entry = map.key(key)?;
+                de.read.begin_key(&mut key);
+                let k = de.read.parse_str(&mut de.buffer)?;
+                R::end_key(&mut key, &k);
+                if len >= de.limits.max_elements {
+                    de.stack.push((outer, Layer::Map(map, len + 1, key)));
+                    return Err(Error::from(ErrorKind::TooManyElements));
+                }
+                let entry = match map.key(&k) {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        de.stack.push((outer, Layer::Map(map, len + 1, key)));
+                        return Err(err);
+                    }
+                };
                 let next = NonNull::from(entry);
-                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor>) };
+                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor<'de>>) };
                 match de.parse_whitespace() {
                     Some(b':') => de.bump(),
-                    _ => return Err(Error),
+                    _ => return Err(syntax()),
                 }
-                de.stack.push((outer, Layer::Map(map)));
+                de.stack.push((outer, Layer::Map(map, len + 1, key)));
             }
         }
     }
 
-    match de.parse_whitespace() {
-        Some(_) => Err(Error),
-        None => Ok(()),
+    Ok(())
+}
+
+// One level of nesting on the way to a value: an array along with the number
+// of its elements begun so far, or an object along with what the input needs
+// in order to produce the current key again.
+pub(super) enum Segment<'k, K> {
+    Seq(usize),
+    Map(&'k K),
+}
+
+// Render the location of a value, such as `.users[3].address`, from the levels
+// of nesting around it. The top-level value is `.`. Keys are produced again by
+// the input only now.
+fn render_path<'de, 'k, R, I>(read: &R, buffer: &mut Vec<u8>, segments: I) -> String
+where
+    R: Read<'de>,
+    I: IntoIterator<Item = Segment<'k, R::Key>>,
+    R::Key: 'k,
+{
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Seq(len) => {
+                path.push('[');
+                path.push_str(itoa::Buffer::new().format(len.saturating_sub(1)));
+                path.push(']');
+            }
+            Segment::Map(key) => {
+                path.push('.');
+                read.push_key(key, &mut path, buffer);
+            }
+        }
     }
+    if path.is_empty() {
+        path.push('.');
+    }
+    path
 }
 
-enum Event<'a> {
+pub(super) enum Event<'de, 'a> {
     Null,
     Bool(bool),
-    Str(&'a str),
+    Str(Reference<'de, 'a>),
     Negative(i64),
     Nonnegative(u64),
     Float(f64),
@@ -199,15 +486,20 @@ macro_rules! overflow {
     };
 }
 
-impl<'a, 'b> Deserializer<'a, 'b> {
+impl<'de, R: Read<'de>> Deserializer<'de, R> {
+    // Render the location of the innermost value being deserialized from the
+    // layers on the stack.
+    #[cold]
+    fn path(&mut self) -> String {
+        let segments = self.stack.iter().map(|(_, layer)| match layer {
+            Layer::Seq(_, len) => Segment::Seq(*len),
+            Layer::Map(_, _, key) => Segment::Map(key),
+        });
+        render_path(&self.read, &mut self.buffer, segments)
+    }
+
     fn next(&mut self) -> Option<u8> {
-        if self.pos < self.input.len() {
-            let ch = self.input[self.pos];
-            self.pos += 1;
-            Some(ch)
-        } else {
-            None
-        }
+        self.read.next()
     }
 
     fn next_or_nul(&mut self) -> u8 {
@@ -215,156 +507,18 @@ impl<'a, 'b> Deserializer<'a, 'b> {
     }
 
     fn peek(&mut self) -> Option<u8> {
-        if self.pos < self.input.len() {
-            Some(self.input[self.pos])
-        } else {
-            None
-        }
+        self.read.peek()
     }
 
     fn peek_or_nul(&mut self) -> u8 {
         self.peek().unwrap_or(b'\0')
     }
 
-    fn bump(&mut self) {
-        self.pos += 1;
+    pub(super) fn bump(&mut self) {
+        self.read.bump();
     }
 
-    fn parse_str(&mut self) -> Result<&str> {
-        fn result(bytes: &[u8]) -> &str {
-            // The deserialization input came in as &str with a UTF-8 guarantee,
-            // and the \u-escapes are checked along the way, so don't need to
-            // check here.
-            unsafe { str::from_utf8_unchecked(bytes) }
-        }
-
-        // Index of the first byte not yet copied into the scratch space.
-        let mut start = self.pos;
-        self.buffer.clear();
-
-        loop {
-            while self.pos < self.input.len() && !ESCAPE[usize::from(self.input[self.pos])] {
-                self.pos += 1;
-            }
-            if self.pos == self.input.len() {
-                return Err(Error);
-            }
-            match self.input[self.pos] {
-                b'"' => {
-                    if self.buffer.is_empty() {
-                        // Fast path: return a slice of the raw JSON without any
-                        // copying.
-                        let borrowed = &self.input[start..self.pos];
-                        self.pos += 1;
-                        return Ok(result(borrowed));
-                    } else {
-                        self.buffer.extend_from_slice(&self.input[start..self.pos]);
-                        self.pos += 1;
-                        return Ok(result(&self.buffer));
-                    }
-                }
-                b'\\' => {
-                    self.buffer.extend_from_slice(&self.input[start..self.pos]);
-                    self.pos += 1;
-                    self.parse_escape()?;
-                    start = self.pos;
-                }
-                _ => {
-                    return Err(Error);
-                }
-            }
-        }
-    }
-
-    fn next_or_eof(&mut self) -> Result<u8> {
-        self.next().ok_or(Error)
-    }
-
-    /// Parses a JSON escape sequence and appends it into the scratch space. Assumes
-    /// the previous byte read was a backslash.
-    fn parse_escape(&mut self) -> Result<()> {
-        let ch = self.next_or_eof()?;
-
-        match ch {
-            b'"' => self.buffer.push(b'"'),
-            b'\\' => self.buffer.push(b'\\'),
-            b'/' => self.buffer.push(b'/'),
-            b'b' => self.buffer.push(b'\x08'),
-            b'f' => self.buffer.push(b'\x0c'),
-            b'n' => self.buffer.push(b'\n'),
-            b'r' => self.buffer.push(b'\r'),
-            b't' => self.buffer.push(b'\t'),
-            b'u' => {
-                let c = match self.decode_hex_escape()? {
-                    0xDC00..=0xDFFF => {
-                        return Err(Error);
-                    }
-
-                    // Non-BMP characters are encoded as a sequence of
-                    // two hex escapes, representing UTF-16 surrogates.
-                    n1 @ 0xD800..=0xDBFF => {
-                        if self.next_or_eof()? != b'\\' {
-                            return Err(Error);
-                        }
-                        if self.next_or_eof()? != b'u' {
-                            return Err(Error);
-                        }
-
-                        let n2 = self.decode_hex_escape()?;
-
-                        if n2 < 0xDC00 || n2 > 0xDFFF {
-                            return Err(Error);
-                        }
-
-                        let n = (u32::from(n1 - 0xD800) << 10 | u32::from(n2 - 0xDC00)) + 0x1_0000;
-
-                        match char::from_u32(n) {
-                            Some(c) => c,
-                            None => {
-                                return Err(Error);
-                            }
-                        }
-                    }
-
-                    n => match char::from_u32(u32::from(n)) {
-                        Some(c) => c,
-                        None => {
-                            return Err(Error);
-                        }
-                    },
-                };
-
-                self.buffer
-                    .extend_from_slice(c.encode_utf8(&mut [0_u8; 4]).as_bytes());
-            }
-            _ => {
-                return Err(Error);
-            }
-        }
-
-        Ok(())
-    }
-
-    fn decode_hex_escape(&mut self) -> Result<u16> {
-        let mut n = 0;
-        for _ in 0..4 {
-            n = match self.next_or_eof()? {
-                c @ b'0'..=b'9' => n * 16_u16 + u16::from(c - b'0'),
-                b'a' | b'A' => n * 16_u16 + 10_u16,
-                b'b' | b'B' => n * 16_u16 + 11_u16,
-                b'c' | b'C' => n * 16_u16 + 12_u16,
-                b'd' | b'D' => n * 16_u16 + 13_u16,
-                b'e' | b'E' => n * 16_u16 + 14_u16,
-                b'f' | b'F' => n * 16_u16 + 15_u16,
-                _ => {
-                    return Err(Error);
-                }
-            };
-        }
-        Ok(n)
-    }
-
-    fn parse_whitespace(&mut self) -> Option<u8> {
+    pub(super) fn parse_whitespace(&mut self) -> Option<u8> {
         loop {
             match self.peek() {
                 Some(b' ') | Some(b'\n') | Some(b'\t') | Some(b'\r') => {
@@ -381,11 +535,11 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         for expected in ident {
             match self.next() {
                 None => {
-                    return Err(Error);
+                    return Err(syntax());
                 }
                 Some(next) => {
                     if next != *expected {
-                        return Err(Error);
+                        return Err(syntax());
                     }
                 }
             }
@@ -393,12 +547,12 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         Ok(())
     }
 
-    fn parse_integer(&mut self, nonnegative: bool, first_digit: u8) -> Result<Event> {
+    fn parse_integer(&mut self, nonnegative: bool, first_digit: u8) -> Result<Event<'de, '_>> {
         match first_digit {
             b'0' => {
                 // There can be only one leading '0'.
                 match self.peek_or_nul() {
-                    b'0'..=b'9' => Err(Error),
+                    b'0'..=b'9' => Err(syntax()),
                     _ => self.parse_number(nonnegative, 0),
                 }
             }
@@ -432,7 +586,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
                     }
                 }
             }
-            _ => Err(Error),
+            _ => Err(syntax()),
         }
     }
 
@@ -463,7 +617,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         }
     }
 
-    fn parse_number(&mut self, nonnegative: bool, significand: u64) -> Result<Event> {
+    fn parse_number(&mut self, nonnegative: bool, significand: u64) -> Result<Event<'de, '_>> {
         match self.peek_or_nul() {
             b'.' => self.parse_decimal(nonnegative, significand, 0).map(Float),
             b'e' | b'E' => self.parse_exponent(nonnegative, significand, 0).map(Float),
@@ -512,7 +666,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         }
 
         if !at_least_one_digit {
-            return Err(Error);
+            return Err(syntax());
         }
 
         match self.peek_or_nul() {
@@ -545,7 +699,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         let mut exp = match self.next_or_nul() {
             c @ b'0'..=b'9' => i32::from(c - b'0'),
             _ => {
-                return Err(Error);
+                return Err(syntax());
             }
         };
 
@@ -581,7 +735,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
     ) -> Result<f64> {
         // Error instead of +/- infinity.
         if significand != 0 && positive_exp {
-            return Err(Error);
+            return Err(syntax());
         }
 
         while let b'0'..=b'9' = self.peek_or_nul() {
@@ -590,21 +744,30 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         Ok(if nonnegative { 0.0 } else { -0.0 })
     }
 
-    fn event(&mut self) -> Result<Event> {
+    pub(super) fn event(&mut self) -> Result<Event<'de, '_>> {
         let peek = match self.parse_whitespace() {
             Some(b) => b,
-            None => return Err(Error),
+            None => return Err(syntax()),
         };
-        self.bump();
+        // Without a limit there is nothing to count for.
+        if self.limits.max_values != usize::max_value() {
+            self.values += 1;
+            if self.values > self.limits.max_values {
+                return Err(Error::from(ErrorKind::TooManyValues));
+            }
+        }
         match peek {
-            b'"' => self.parse_str().map(Str),
+            b'{' => return self.nest(MapStart),
+            b'[' => return self.nest(SeqStart),
+            _ => self.bump(),
+        }
+        match peek {
+            b'"' => self.read.parse_str(&mut self.buffer).map(Str),
             digit @ b'0'..=b'9' => self.parse_integer(true, digit),
             b'-' => {
                 let first_digit = self.next_or_nul();
                 self.parse_integer(false, first_digit)
             }
-            b'{' => Ok(MapStart),
-            b'[' => Ok(SeqStart),
             b'n' => {
                 self.parse_ident(b"ull")?;
                 Ok(Null)
@@ -617,8 +780,17 @@ impl<'a, 'b> Deserializer<'a, 'b> {
                 self.parse_ident(b"alse")?;
                 Ok(Bool(false))
             }
-            _ => Err(Error),
+            _ => Err(syntax()),
+        }
+    }
+
+    // Only a seq or map pushes another layer, so only those can go too deep.
+    fn nest<'a>(&mut self, event: Event<'de, 'a>) -> Result<Event<'de, 'a>> {
+        if self.stack.len() >= self.limits.max_depth {
+            return Err(Error::from(ErrorKind::TooDeep));
         }
+        self.bump();
+        Ok(event)
     }
 }
 
@@ -630,7 +802,7 @@ fn f64_from_parts(nonnegative: bool, significand: u64, mut exponent: i32) -> Res
                 if exponent >= 0 {
                     f *= pow;
                     if f.is_infinite() {
-                        return Err(Error);
+                        return Err(syntax());
                     }
                 } else {
                     f /= pow;
@@ -642,7 +814,7 @@ fn f64_from_parts(nonnegative: bool, significand: u64, mut exponent: i32) -> Res
                     break;
                 }
                 if exponent >= 0 {
-                    return Err(Error);
+                    return Err(syntax());
                 }
                 f /= 1e308;
                 exponent += 308;
@@ -687,31 +859,3 @@ static POW10: [f64; 309] = [
     1e290, 1e291, 1e292, 1e293, 1e294, 1e295, 1e296, 1e297, 1e298, 1e299, //
     1e300, 1e301, 1e302, 1e303, 1e304, 1e305, 1e306, 1e307, 1e308,
 ];
-
-const CT: bool = true; // control character \x00..=\x1F
-const QU: bool = true; // quote \x22
-const BS: bool = true; // backslash \x5C
-const O: bool = false; // allow unescaped
-
-// Lookup table of bytes that must be escaped. A value of true at index i means
-// that byte i requires an escape sequence in the input.
-#[rustfmt::skip]
-static ESCAPE: [bool; 256] = [
-    //   1   2   3   4   5   6   7   8   9   A   B   C   D   E   F
-    CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, // 0
-    CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, // 1
-     O,  O, QU,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 2
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 3
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 4
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, BS,  O,  O,  O, // 5
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 6
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 7
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 8
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 9
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // A
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // B
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // C
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // D
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // E
-     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // F
-];
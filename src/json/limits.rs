@@ -0,0 +1,209 @@
+use super::de::from_impl;
+use super::read::SliceRead;
+use super::{Parser, StreamDeserializer};
+use crate::de::Deserialize;
+#[cfg(feature = "std")]
+use crate::de::DeserializeOwned;
+use crate::error::{Error, Result};
+
+#[cfg(feature = "std")]
+use super::read::IoRead;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Limits on the input accepted by deserialization, for handling JSON from
+/// untrusted sources.
+///
+/// Nothing is limited by default. Each limit that is exceeded fails
+/// deserialization with its own [`ErrorKind`][crate::ErrorKind].
+///
+/// ```rust
+/// use miniserde::json::{self, Limits, Value};
+/// use miniserde::ErrorKind;
+///
+/// let limits = Limits::new().max_depth(64).max_input_len(1 << 20);
+///
+/// let value: Value = limits.from_str(r#"{"a": [1, 2, 3]}"#)?;
+/// println!("{}", json::to_string(&value));
+///
+/// let hostile = "[".repeat(100_000);
+/// let err = limits.from_str::<Value>(&hostile).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::TooDeep);
+/// # Ok::<(), miniserde::Error>(())
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Limits {
+    pub(super) max_depth: usize,
+    pub(super) max_input_len: usize,
+    pub(super) max_str_len: usize,
+    pub(super) max_elements: usize,
+    pub(super) max_values: usize,
+}
+
+impl Limits {
+    /// No limits.
+    pub fn new() -> Self {
+        Limits {
+            max_depth: usize::max_value(),
+            max_input_len: usize::max_value(),
+            max_str_len: usize::max_value(),
+            max_elements: usize::max_value(),
+            max_values: usize::max_value(),
+        }
+    }
+
+    /// Maximum number of arrays and objects that may be nested inside one
+    /// another, failing with [`ErrorKind::TooDeep`][crate::ErrorKind::TooDeep].
+    /// A scalar at the top level has depth 0, and `[[1]]` has depth 2.
+    pub fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = max;
+        self
+    }
+
+    /// Maximum length of the input in bytes, failing with
+    /// [`ErrorKind::InputTooLong`][crate::ErrorKind::InputTooLong]. Input
+    /// from an `io::Read` is read no further than a little past this limit.
+    pub fn max_input_len(mut self, max: usize) -> Self {
+        self.max_input_len = max;
+        self
+    }
+
+    /// Maximum length in bytes of a string or object key after unescaping,
+    /// failing with
+    /// [`ErrorKind::StringTooLong`][crate::ErrorKind::StringTooLong].
+    pub fn max_str_len(mut self, max: usize) -> Self {
+        self.max_str_len = max;
+        self
+    }
+
+    /// Maximum number of elements in any one array, or entries in any one
+    /// object, failing with
+    /// [`ErrorKind::TooManyElements`][crate::ErrorKind::TooManyElements].
+    pub fn max_elements(mut self, max: usize) -> Self {
+        self.max_elements = max;
+        self
+    }
+
+    /// Maximum number of values in the input altogether, counting every
+    /// array, object, string, number, boolean and null, failing with
+    /// [`ErrorKind::TooManyValues`][crate::ErrorKind::TooManyValues]. When
+    /// deserializing a [`json::Value`][crate::json::Value], this is the number
+    /// of `Value` nodes allocated.
+    pub fn max_values(mut self, max: usize) -> Self {
+        self.max_values = max;
+        self
+    }
+
+    /// Deserialize a JSON string within these limits, as
+    /// [`json::from_str`][crate::json::from_str] does.
+    #[allow(clippy::should_implement_trait, clippy::wrong_self_convention)]
+    pub fn from_str<'de, T: Deserialize<'de>>(&self, j: &'de str) -> Result<T> {
+        let mut out = None;
+        from_impl(SliceRead::utf8(j), self, T::begin(&mut out))?;
+        out.ok_or(Error)
+    }
+
+    /// Deserialize JSON bytes within these limits, as
+    /// [`json::from_slice`][crate::json::from_slice] does.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_slice<'de, T: Deserialize<'de>>(&self, j: &'de [u8]) -> Result<T> {
+        let mut out = None;
+        from_impl(SliceRead::new(j), self, T::begin(&mut out))?;
+        out.ok_or(Error)
+    }
+
+    /// Deserialize JSON from an `io::Read` within these limits, as
+    /// [`json::from_reader`][crate::json::from_reader] does.
+    #[cfg(feature = "std")]
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_reader<R: io::Read, T: DeserializeOwned>(&self, reader: R) -> Result<T> {
+        let mut out = None;
+        from_impl(IoRead::new(reader), self, T::begin(&mut out))?;
+        out.ok_or(Error)
+    }
+
+    /// Deserialize a stream of values in a string within these limits, as
+    /// [`StreamDeserializer::from_str`] does.
+    ///
+    /// The input length limit applies to the stream as a whole, and every
+    /// other limit to each value separately.
+    ///
+    /// ```rust
+    /// use miniserde::json::Limits;
+    /// use miniserde::ErrorKind;
+    ///
+    /// let limits = Limits::new().max_depth(1);
+    /// let mut stream = limits.stream_from_str::<Vec<u32>>("[1, 2]\n[[3]]\n[4]\n");
+    /// assert_eq!(stream.next().unwrap()?, [1, 2]);
+    /// assert_eq!(stream.next().unwrap().unwrap_err().kind(), ErrorKind::TooDeep);
+    /// assert_eq!(stream.next().unwrap()?, [4]);
+    /// # Ok::<(), miniserde::Error>(())
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn stream_from_str<'de, T: Deserialize<'de>>(
+        &self,
+        j: &'de str,
+    ) -> StreamDeserializer<'de, T> {
+        StreamDeserializer::with_limits(SliceRead::utf8(j), self)
+    }
+
+    /// Deserialize a stream of values in a byte slice within these limits, as
+    /// [`StreamDeserializer::from_slice`] does.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn stream_from_slice<'de, T: Deserialize<'de>>(
+        &self,
+        j: &'de [u8],
+    ) -> StreamDeserializer<'de, T> {
+        StreamDeserializer::with_limits(SliceRead::new(j), self)
+    }
+
+    /// Deserialize a stream of values read from an `io::Read` within these
+    /// limits, as [`StreamDeserializer::from_reader`] does.
+    #[cfg(feature = "std")]
+    #[allow(clippy::wrong_self_convention)]
+    pub fn stream_from_reader<'a, R: io::Read + 'a, T: DeserializeOwned>(
+        &self,
+        reader: R,
+    ) -> StreamDeserializer<'a, T> {
+        StreamDeserializer::with_limits(IoRead::new(reader), self)
+    }
+
+    /// Parse the tokens of a string within these limits, as
+    /// [`Parser::from_str`] does.
+    ///
+    /// ```rust
+    /// use miniserde::json::Limits;
+    /// use miniserde::ErrorKind;
+    ///
+    /// let mut parser = Limits::new().max_depth(1).parser_from_str("[[1]]");
+    /// assert!(parser.next().unwrap().is_ok());
+    /// let err = parser.next().unwrap().unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::TooDeep);
+    /// # Ok::<(), miniserde::Error>(())
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn parser_from_str<'a>(&self, j: &'a str) -> Parser<'a> {
+        Parser::with_limits(SliceRead::utf8(j), self)
+    }
+
+    /// Parse the tokens of a byte slice within these limits, as
+    /// [`Parser::from_slice`] does.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn parser_from_slice<'a>(&self, j: &'a [u8]) -> Parser<'a> {
+        Parser::with_limits(SliceRead::new(j), self)
+    }
+
+    /// Parse the tokens of an `io::Read` within these limits, as
+    /// [`Parser::from_reader`] does.
+    #[cfg(feature = "std")]
+    #[allow(clippy::wrong_self_convention)]
+    pub fn parser_from_reader<'a, R: io::Read + 'a>(&self, reader: R) -> Parser<'a> {
+        Parser::with_limits(IoRead::new(reader), self)
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits::new()
+    }
+}
@@ -7,7 +7,20 @@ mod ser;
 pub use self::ser::to_string;
 
 mod de;
-pub use self::de::from_str;
+#[cfg(feature = "std")]
+pub use self::de::from_reader;
+pub use self::de::{from_slice, from_str};
+
+mod read;
+
+mod limits;
+pub use self::limits::Limits;
+
+mod stream;
+pub use self::stream::StreamDeserializer;
+
+mod parser;
+pub use self::parser::{Parser, Token};
 
 mod value;
 pub use self::value::Value;
@@ -1,7 +1,10 @@
+use crate::de::{self, Deserialize, Visitor};
+use crate::error::Result;
 use crate::json::{drop, Value};
 use crate::private;
 use crate::ser::{self, Fragment, Serialize};
-use alloc::borrow::Cow;
+use crate::Place;
+use alloc::borrow::{Cow, ToOwned};
 use alloc::boxed::Box;
 use alloc::collections::{btree_map, BTreeMap};
 use alloc::string::String;
@@ -90,6 +93,61 @@ impl FromIterator<(String, Value)> for Object {
     }
 }
 
+impl Serialize for Object {
+    fn begin(&self) -> Fragment {
+        private::stream_object(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Object {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<Object> {
+            fn map<'a>(&'a mut self) -> Result<Box<dyn de::Map<'de> + 'a>>
+            where
+                'de: 'a,
+            {
+                Ok(Box::new(ObjectBuilder {
+                    out: &mut self.out,
+                    object: Object::new(),
+                    key: None,
+                    value: None,
+                }))
+            }
+        }
+
+        struct ObjectBuilder<'a> {
+            out: &'a mut Option<Object>,
+            object: Object,
+            key: Option<String>,
+            value: Option<Value>,
+        }
+
+        impl<'a> ObjectBuilder<'a> {
+            fn shift(&mut self) {
+                if let (Some(k), Some(v)) = (self.key.take(), self.value.take()) {
+                    self.object.insert(k, v);
+                }
+            }
+        }
+
+        impl<'a, 'de> de::Map<'de> for ObjectBuilder<'a> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+                self.shift();
+                self.key = Some(k.to_owned());
+                Ok(Deserialize::begin(&mut self.value))
+            }
+
+            fn finish(&mut self) -> Result<()> {
+                self.shift();
+                *self.out = Some(mem::replace(&mut self.object, Object::new()));
+                Ok(())
+            }
+        }
+
+        Place::new(out)
+    }
+}
+
 impl private {
     pub fn stream_object(object: &Object) -> Fragment {
         struct ObjectIter<'a>(btree_map::Iter<'a, String, Value>);
@@ -0,0 +1,360 @@
+use super::de::{Deserializer, Event, Segment};
+use super::read::{syntax, Read, Reference, SliceRead};
+use super::{Limits, Number};
+use crate::error::{Error, ErrorKind, Result};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use super::read::IoRead;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Pull parser that produces a JSON document one token at a time, without
+/// deserializing it into any Rust type.
+///
+/// Arrays and objects are produced as a start token, their contents, and a
+/// matching end token. Within an object each value is preceded by its `Key`.
+/// The parser checks that the input is well formed as it goes, so the tokens
+/// produced are always properly nested, and malformed input fails with the
+/// same errors as [`json::from_str`][crate::json::from_str]. Once the document
+/// has ended, or after an error, `next` returns None.
+///
+/// Each token comes with the byte offset in the input at which it begins.
+///
+/// ```rust
+/// use miniserde::json::{Parser, Token};
+///
+/// // Find every string in a document, however deeply nested.
+/// let j = r#"{"users": [{"name": "alice", "roles": ["admin"]}, {"name": "bob"}]}"#;
+///
+/// let mut parser = Parser::from_str(j);
+/// let mut strings = Vec::new();
+/// while let Some(token) = parser.next() {
+///     if let (offset, Token::Str(s)) = token? {
+///         strings.push((s.to_owned(), offset));
+///     }
+/// }
+///
+/// assert_eq!(strings[0], ("alice".to_owned(), 20));
+/// assert_eq!(strings.len(), 3);
+/// # Ok::<(), miniserde::Error>(())
+/// ```
+pub struct Parser<'a> {
+    tokens: Box<dyn Tokens + 'a>,
+}
+
+/// A single token of a JSON document, as produced by [`Parser`].
+#[derive(Clone, Debug)]
+pub enum Token<'a> {
+    Null,
+    Bool(bool),
+    /// A number. An integer is held as `U64` if nonnegative and `I64` if
+    /// negative. A number with a fraction or exponent, or an integer out of
+    /// range of those, is held as `F64`.
+    Number(Number),
+    /// A string value, with its escape sequences already decoded.
+    Str(&'a str),
+    SeqStart,
+    SeqEnd,
+    MapStart,
+    MapEnd,
+    /// An object key, with its escape sequences already decoded. Every string
+    /// in key position of an object is produced as `Key` rather than `Str`,
+    /// followed by the tokens of its value.
+    Key(&'a str),
+}
+
+impl<'a> Parser<'a> {
+    /// Parse the tokens of a string.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(j: &'a str) -> Self {
+        Parser::new(SliceRead::utf8(j))
+    }
+
+    /// Parse the tokens of a byte slice, which is checked for UTF-8 only
+    /// inside of string literals as in
+    /// [`json::from_slice`][crate::json::from_slice].
+    pub fn from_slice(j: &'a [u8]) -> Self {
+        Parser::new(SliceRead::new(j))
+    }
+
+    /// Parse the tokens of an `io::Read`, as they are read, through the same
+    /// fixed-size buffer as [`json::from_reader`][crate::json::from_reader].
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: io::Read + 'a>(reader: R) -> Self {
+        Parser::new(IoRead::new(reader))
+    }
+
+    fn new<R: Read<'a> + 'a>(read: R) -> Self {
+        Parser::with_limits(read, &Limits::new())
+    }
+
+    pub(super) fn with_limits<R: Read<'a> + 'a>(read: R, limits: &Limits) -> Self {
+        Parser {
+            tokens: Box::new(Tokenizer {
+                de: Deserializer::new(read, limits),
+                limits: *limits,
+                stack: Vec::new(),
+                string: String::new(),
+                offset: 0,
+                state: State::Start,
+            }),
+        }
+    }
+
+    /// Produce the next token along with its offset, or None at the end of
+    /// the document.
+    ///
+    /// This is not an `Iterator` because the strings in the token borrow from
+    /// the parser, and are good only until the next call.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<Result<(usize, Token)>> {
+        self.tokens.next()
+    }
+}
+
+// Object-safe view of a Tokenizer, so that Parser does not need to name the
+// type of its input.
+trait Tokens {
+    fn next(&mut self) -> Option<Result<(usize, Token)>>;
+}
+
+struct Tokenizer<'de, R: Read<'de>> {
+    // Used only for its lexer, so nothing is ever on the deserializer's own
+    // stack, and the depth and element limits are checked against ours.
+    de: Deserializer<'de, R>,
+    limits: Limits,
+    // Arrays and objects entered but not yet ended, along with the number of
+    // elements begun so far and for an object the current key, for the sake
+    // of error messages.
+    stack: Vec<Nest<R::Key>>,
+    // The most recent string or key, if it could not be lent straight out of
+    // the input. Copying it here, rather than lending it out of the
+    // deserializer's scratch space, leaves the deserializer free to attach a
+    // position to any error.
+    string: String,
+    offset: usize,
+    state: State,
+}
+
+enum Nest<K> {
+    Seq(usize),
+    Map(usize, K),
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum State {
+    // Nothing produced yet.
+    Start,
+    // Ready for the next token inside of an array or object, or for the end
+    // of the input once the stack is empty.
+    Next,
+    // Just produced a key, so a colon and a value come next.
+    Value,
+    // At the end of the document or after an error.
+    Done,
+}
+
+impl<'de, R: Read<'de>> Tokens for Tokenizer<'de, R> {
+    fn next(&mut self) -> Option<Result<(usize, Token)>> {
+        let token = match self.step() {
+            Ok(Some(token)) => token,
+            Ok(None) => {
+                self.state = State::Done;
+                return None;
+            }
+            Err(err) => {
+                self.state = State::Done;
+                return Some(Err(self.fail(err)));
+            }
+        };
+        Some(Ok((
+            self.offset,
+            match token {
+                Step::Null => Token::Null,
+                Step::Bool(b) => Token::Bool(b),
+                Step::Number(n) => Token::Number(n),
+                Step::Str(s) => Token::Str(s.unwrap_or(&self.string)),
+                Step::SeqStart => Token::SeqStart,
+                Step::SeqEnd => Token::SeqEnd,
+                Step::MapStart => Token::MapStart,
+                Step::MapEnd => Token::MapEnd,
+                Step::Key(k) => Token::Key(k.unwrap_or(&self.string)),
+            },
+        )))
+    }
+}
+
+// A token with its string, if it borrows from the input, or else with the
+// string left behind in Tokenizer::string.
+enum Step<'de> {
+    Null,
+    Bool(bool),
+    Number(Number),
+    Str(Option<&'de str>),
+    SeqStart,
+    SeqEnd,
+    MapStart,
+    MapEnd,
+    Key(Option<&'de str>),
+}
+
+impl<'de, R: Read<'de>> Tokenizer<'de, R> {
+    fn step(&mut self) -> Result<Option<Step<'de>>> {
+        match self.state {
+            State::Start => {
+                self.state = State::Next;
+                return self.value().map(Some);
+            }
+            State::Value => {
+                self.state = State::Next;
+                match self.de.parse_whitespace() {
+                    Some(b':') => self.de.bump(),
+                    _ => {
+                        self.stack.pop();
+                        return Err(syntax());
+                    }
+                }
+                return self.value().map(Some);
+            }
+            State::Next => {}
+            State::Done => return Ok(None),
+        }
+
+        // Errors in between the elements of an array or the entries of an
+        // object are attributed to the array or object as a whole, by popping
+        // it before failing, the same as in the deserializer.
+        let peek = self.begin_token();
+        match self.stack.last_mut() {
+            None => match peek {
+                Some(_) => Err(syntax()),
+                None if self.de.truncated() => Err(syntax()),
+                None => Ok(None),
+            },
+            Some(Nest::Seq(len)) => {
+                if peek == Some(b']') {
+                    self.de.bump();
+                    self.stack.pop();
+                    return Ok(Some(Step::SeqEnd));
+                }
+                if *len > 0 {
+                    if peek != Some(b',') {
+                        self.stack.pop();
+                        return Err(syntax());
+                    }
+                    self.de.bump();
+                }
+                *len += 1;
+                if *len > self.limits.max_elements {
+                    return Err(Error::from(ErrorKind::TooManyElements));
+                }
+                self.value().map(Some)
+            }
+            Some(Nest::Map(..)) => {
+                if peek == Some(b'}') {
+                    self.de.bump();
+                    self.stack.pop();
+                    return Ok(Some(Step::MapEnd));
+                }
+                self.key(peek).map(Some)
+            }
+        }
+    }
+
+    fn key(&mut self, peek: Option<u8>) -> Result<Step<'de>> {
+        let k = match self.parse_key(peek) {
+            Ok(k) => k,
+            Err(err) => {
+                self.stack.pop();
+                return Err(err);
+            }
+        };
+        // Reported with the key in the path, the same as in the deserializer.
+        if let Some(Nest::Map(len, _)) = self.stack.last() {
+            if *len > self.limits.max_elements {
+                return Err(Error::from(ErrorKind::TooManyElements));
+            }
+        }
+        self.state = State::Value;
+        Ok(Step::Key(k))
+    }
+
+    fn parse_key(&mut self, mut peek: Option<u8>) -> Result<Option<&'de str>> {
+        let (len, key) = match self.stack.last_mut() {
+            Some(Nest::Map(len, key)) => (len, key),
+            _ => unreachable!(),
+        };
+        if *len > 0 {
+            if peek != Some(b',') {
+                return Err(syntax());
+            }
+            self.de.bump();
+            peek = self.de.parse_whitespace();
+            self.offset = self.de.offset();
+        }
+        if peek != Some(b'"') {
+            return Err(syntax());
+        }
+        self.de.bump();
+        *len += 1;
+        let k = self.de.parse_key(key)?;
+        Ok(lend(k, &mut self.string))
+    }
+
+    fn value(&mut self) -> Result<Step<'de>> {
+        let peek = self.begin_token();
+        if (peek == Some(b'[') || peek == Some(b'{')) && self.stack.len() >= self.limits.max_depth {
+            return Err(Error::from(ErrorKind::TooDeep));
+        }
+        let string = &mut self.string;
+        let step = match self.de.event()? {
+            Event::Null => Step::Null,
+            Event::Bool(b) => Step::Bool(b),
+            Event::Str(s) => Step::Str(lend(s, string)),
+            Event::Negative(n) => Step::Number(Number::I64(n)),
+            Event::Nonnegative(n) => Step::Number(Number::U64(n)),
+            Event::Float(n) => Step::Number(Number::F64(n)),
+            Event::SeqStart => Step::SeqStart,
+            Event::MapStart => Step::MapStart,
+        };
+        match step {
+            Step::SeqStart => self.stack.push(Nest::Seq(0)),
+            Step::MapStart => self.stack.push(Nest::Map(0, R::Key::default())),
+            _ => {}
+        }
+        Ok(step)
+    }
+
+    // Skip whitespace ahead of the next token, noting where it begins.
+    fn begin_token(&mut self) -> Option<u8> {
+        let peek = self.de.parse_whitespace();
+        self.offset = self.de.offset();
+        peek
+    }
+
+    #[cold]
+    fn fail(&mut self, err: Error) -> Error {
+        let err = self.de.error(err);
+        let segments = self.stack.iter().map(|nest| match nest {
+            Nest::Seq(len) => Segment::Seq(*len),
+            Nest::Map(_, key) => Segment::Map(key),
+        });
+        let path = self.de.path_of(segments);
+        err.with_path(path)
+    }
+}
+
+// A string that borrows from the input can be lent out as is. Any other is
+// copied into `string`.
+fn lend<'de>(s: Reference<'de, '_>, string: &mut String) -> Option<&'de str> {
+    match s {
+        Reference::Borrowed(s) => Some(s),
+        Reference::Copied(s) => {
+            string.clear();
+            string.push_str(s);
+            None
+        }
+    }
+}
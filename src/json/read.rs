@@ -0,0 +1,615 @@
+// Sources of input for the JSON deserializer: a slice that is in memory in its
+// entirety, or an io::Read consumed through a fixed-size window.
+
+use crate::error::{Error, ErrorKind, Result};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::char;
+use core::ops::Deref;
+use core::str;
+
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use std::io;
+
+pub(crate) trait Read<'de> {
+    // Enough information to produce a map key again when rendering the path of
+    // an error, after the deserializer has moved on past it.
+    type Key: Default;
+
+    fn peek(&mut self) -> Option<u8>;
+
+    // Advance past the byte returned by the most recent peek.
+    fn bump(&mut self);
+
+    fn next(&mut self) -> Option<u8> {
+        let ch = self.peek();
+        if ch.is_some() {
+            self.bump();
+        }
+        ch
+    }
+
+    // Offset in the input of the next byte to be consumed.
+    fn offset(&self) -> usize;
+
+    // Stop at `max_len` bytes of input, and reject strings longer than
+    // `max_str_len` bytes.
+    fn limit(&mut self, max_len: usize, max_str_len: usize);
+
+    // Whether the input came to an end on account of an I/O error or the
+    // input length limit, rather than by actually ending.
+    fn truncated(&self) -> bool;
+
+    // Parse the rest of a string whose opening quote has already been
+    // consumed. Anything that cannot be returned as a slice of the input is
+    // assembled in `scratch`.
+    fn parse_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's>>;
+
+    // Called with the key of the current map entry before and after parsing
+    // the entry's key.
+    fn begin_key(&self, key: &mut Self::Key);
+    fn end_key(key: &mut Self::Key, s: &str);
+
+    // Append the string that was parsed into `key` to the path of an error.
+    fn push_key(&self, key: &Self::Key, path: &mut String, scratch: &mut Vec<u8>);
+
+    // Attach the current position to an error.
+    fn error(&mut self, err: Error) -> Error;
+
+    // Go back to the start of the line holding the next byte to be consumed,
+    // provided that line starts after `offset`. Returns false if it does not,
+    // that is, if no newline has been consumed since `offset`.
+    fn rewind_line(&mut self, offset: usize) -> bool;
+}
+
+// A string parsed out of the input, which either borrows from the input itself
+// for as long as 'b, or was copied into scratch space that is good only until
+// the next string is parsed.
+pub(crate) enum Reference<'b, 'c> {
+    Borrowed(&'b str),
+    Copied(&'c str),
+}
+
+impl<'b, 'c> Deref for Reference<'b, 'c> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match *self {
+            Reference::Borrowed(s) => s,
+            Reference::Copied(s) => s,
+        }
+    }
+}
+
+pub(crate) struct SliceRead<'a> {
+    slice: &'a [u8],
+    index: usize,
+    // Whether the slice came from a &str, so that strings parsed out of it do
+    // not need their UTF-8 checked again.
+    utf8: bool,
+    max_str_len: usize,
+    // Whether the slice was cut short to the input length limit.
+    exceeded: bool,
+    // Whether the lexer has tried to read past the end of the slice.
+    eof: bool,
+}
+
+impl<'a> SliceRead<'a> {
+    pub fn new(slice: &'a [u8]) -> Self {
+        SliceRead {
+            slice,
+            index: 0,
+            utf8: false,
+            max_str_len: usize::max_value(),
+            exceeded: false,
+            eof: false,
+        }
+    }
+
+    pub fn utf8(s: &'a str) -> Self {
+        SliceRead {
+            slice: s.as_bytes(),
+            index: 0,
+            utf8: true,
+            max_str_len: usize::max_value(),
+            exceeded: false,
+            eof: false,
+        }
+    }
+
+    fn result<'s>(&self, bytes: &'s [u8]) -> Result<&'s str> {
+        if self.utf8 {
+            // The deserialization input came in as &str with a UTF-8
+            // guarantee, and the \u-escapes are checked along the way, so
+            // don't need to check here.
+            Ok(unsafe { str::from_utf8_unchecked(bytes) })
+        } else {
+            str::from_utf8(bytes).map_err(|_| syntax())
+        }
+    }
+}
+
+impl<'a> Read<'a> for SliceRead<'a> {
+    // Offset of the first byte after the key's opening quote.
+    type Key = usize;
+
+    #[inline]
+    fn peek(&mut self) -> Option<u8> {
+        if self.index < self.slice.len() {
+            Some(self.slice[self.index])
+        } else {
+            self.eof = true;
+            None
+        }
+    }
+
+    #[inline]
+    fn bump(&mut self) {
+        self.index += 1;
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        if self.index < self.slice.len() {
+            let ch = self.slice[self.index];
+            self.index += 1;
+            Some(ch)
+        } else {
+            self.eof = true;
+            None
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.index
+    }
+
+    fn limit(&mut self, max_len: usize, max_str_len: usize) {
+        if self.slice.len() > max_len {
+            self.slice = &self.slice[..max_len];
+            self.exceeded = true;
+        }
+        self.max_str_len = max_str_len;
+    }
+
+    fn truncated(&self) -> bool {
+        self.exceeded
+    }
+
+    fn parse_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'a, 's>> {
+        // Index of the first byte not yet copied into the scratch space.
+        let mut start = self.index;
+        scratch.clear();
+
+        loop {
+            while self.index < self.slice.len() && !ESCAPE[usize::from(self.slice[self.index])] {
+                self.index += 1;
+            }
+            if self.index == self.slice.len() {
+                self.eof = true;
+                return Err(syntax());
+            }
+            match self.slice[self.index] {
+                b'"' => {
+                    if scratch.len() + (self.index - start) > self.max_str_len {
+                        return Err(Error::from(ErrorKind::StringTooLong));
+                    }
+                    // Invalid UTF-8 is reported at the closing quote.
+                    let s = if scratch.is_empty() {
+                        // Fast path: return a slice of the raw JSON without any
+                        // copying.
+                        let slice = self.slice;
+                        Reference::Borrowed(self.result(&slice[start..self.index])?)
+                    } else {
+                        scratch.extend_from_slice(&self.slice[start..self.index]);
+                        Reference::Copied(self.result(scratch)?)
+                    };
+                    self.index += 1;
+                    return Ok(s);
+                }
+                b'\\' => {
+                    scratch.extend_from_slice(&self.slice[start..self.index]);
+                    self.index += 1;
+                    parse_escape(self, scratch)?;
+                    start = self.index;
+                }
+                _ => {
+                    return Err(syntax());
+                }
+            }
+        }
+    }
+
+    fn begin_key(&self, key: &mut usize) {
+        *key = self.index;
+    }
+
+    fn end_key(_key: &mut usize, _s: &str) {}
+
+    fn push_key(&self, key: &usize, path: &mut String, scratch: &mut Vec<u8>) {
+        // Parse the key again, without moving on from where the input is up to.
+        let mut read = SliceRead {
+            index: *key,
+            ..*self
+        };
+        if let Ok(key) = read.parse_str(scratch) {
+            path.push_str(&key);
+        }
+    }
+
+    fn error(&mut self, err: Error) -> Error {
+        let err =
+            if self.exceeded && self.index == self.slice.len() && err.kind() == ErrorKind::Syntax {
+                Error::from(ErrorKind::InputTooLong)
+            } else {
+                err
+            };
+        // A syntax error right at the end of the input is only for want of
+        // more input if the lexer actually went looking for it.
+        let eof = self.eof && self.index == self.slice.len();
+        err.at(self.slice, self.index, eof)
+    }
+
+    fn rewind_line(&mut self, offset: usize) -> bool {
+        match self.slice[offset..self.index]
+            .iter()
+            .rposition(|&b| b == b'\n')
+        {
+            Some(i) => {
+                self.index = offset + i + 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Size of the window through which an io::Read is consumed. Only strings,
+// which are copied out of the window as they are parsed, take up memory
+// proportional to the size of what is in the input.
+#[cfg(feature = "std")]
+const WINDOW: usize = 8 * 1024;
+
+#[cfg(feature = "std")]
+pub(crate) struct IoRead<R> {
+    reader: R,
+    window: Box<[u8]>,
+    // Number of bytes of the window that hold input, and index of the next
+    // byte to be consumed.
+    len: usize,
+    index: usize,
+    // Offset in the input of window[0], along with the line it is on and the
+    // offset of the start of that line. Consumed bytes are scanned for
+    // newlines before they are discarded from the window. The consumed part of
+    // the current line is kept if it is short, for rewind_line.
+    offset: usize,
+    line: usize,
+    line_start: usize,
+    max_len: usize,
+    max_str_len: usize,
+    // Whether the reader returned an error other than Interrupted, or more
+    // than max_len bytes.
+    failed: bool,
+    exceeded: bool,
+    // Whether the input has come to an end, for any reason, as opposed to the
+    // window merely having been used up.
+    eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> IoRead<R> {
+    pub fn new(reader: R) -> Self {
+        IoRead {
+            reader,
+            window: vec![0; WINDOW].into_boxed_slice(),
+            len: 0,
+            index: 0,
+            offset: 0,
+            line: 1,
+            line_start: 0,
+            max_len: usize::max_value(),
+            max_str_len: usize::max_value(),
+            failed: false,
+            exceeded: false,
+            eof: false,
+        }
+    }
+
+    // Discard the consumed part of the window and read more input into it.
+    // Returns false at the end of the input, if the reader failed, or once the
+    // input length limit is reached.
+    #[cold]
+    fn fill(&mut self) -> bool {
+        self.discard();
+        while !self.failed && !self.exceeded {
+            match self.reader.read(&mut self.window[self.len..]) {
+                Ok(n) => {
+                    let remaining = self.max_len - (self.offset + self.len);
+                    self.exceeded = n > remaining;
+                    self.len += n.min(remaining);
+                    self.eof = self.index == self.len;
+                    return !self.eof;
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => self.failed = true,
+            }
+        }
+        self.eof = true;
+        false
+    }
+
+    fn discard(&mut self) {
+        let (offset, line, line_start) = self.position();
+        let keep = if line_start >= self.offset && offset - line_start <= WINDOW / 2 {
+            offset - line_start
+        } else {
+            0
+        };
+        self.window[..self.index].rotate_left(self.index - keep);
+        self.offset = offset - keep;
+        self.line = line;
+        self.line_start = line_start;
+        self.len = keep;
+        self.index = keep;
+    }
+
+    // Offset, line number and offset of the start of the line of the next
+    // byte to be consumed.
+    fn position(&self) -> (usize, usize, usize) {
+        let mut line = self.line;
+        let mut line_start = self.line_start;
+        for (i, &b) in self.window[..self.index].iter().enumerate() {
+            if b == b'\n' {
+                line += 1;
+                line_start = self.offset + i + 1;
+            }
+        }
+        (self.offset + self.index, line, line_start)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, R: io::Read> Read<'de> for IoRead<R> {
+    // The key itself, since the input it came from may have been discarded.
+    type Key = String;
+
+    #[inline]
+    fn peek(&mut self) -> Option<u8> {
+        if self.index < self.len || self.fill() {
+            Some(self.window[self.index])
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn bump(&mut self) {
+        self.index += 1;
+    }
+
+    fn offset(&self) -> usize {
+        self.offset + self.index
+    }
+
+    fn limit(&mut self, max_len: usize, max_str_len: usize) {
+        self.max_len = max_len;
+        self.max_str_len = max_str_len;
+    }
+
+    fn truncated(&self) -> bool {
+        self.failed || self.exceeded
+    }
+
+    fn parse_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's>> {
+        // Index of the first byte not yet copied into the scratch space.
+        let mut start = self.index;
+        scratch.clear();
+
+        loop {
+            while self.index < self.len && !ESCAPE[usize::from(self.window[self.index])] {
+                self.index += 1;
+            }
+            if self.index == self.len {
+                scratch.extend_from_slice(&self.window[start..self.index]);
+                if scratch.len() > self.max_str_len {
+                    return Err(Error::from(ErrorKind::StringTooLong));
+                }
+                if !self.fill() {
+                    return Err(syntax());
+                }
+                start = self.index;
+                continue;
+            }
+            match self.window[self.index] {
+                b'"' => {
+                    if scratch.len() + (self.index - start) > self.max_str_len {
+                        return Err(Error::from(ErrorKind::StringTooLong));
+                    }
+                    // Invalid UTF-8 is reported at the closing quote.
+                    let bytes = if scratch.is_empty() {
+                        &self.window[start..self.index]
+                    } else {
+                        scratch.extend_from_slice(&self.window[start..self.index]);
+                        &scratch[..]
+                    };
+                    let s = str::from_utf8(bytes).map_err(|_| syntax())?;
+                    self.index += 1;
+                    return Ok(Reference::Copied(s));
+                }
+                b'\\' => {
+                    scratch.extend_from_slice(&self.window[start..self.index]);
+                    self.index += 1;
+                    parse_escape(self, scratch)?;
+                    start = self.index;
+                }
+                _ => {
+                    return Err(syntax());
+                }
+            }
+        }
+    }
+
+    fn begin_key(&self, _key: &mut String) {}
+
+    fn end_key(key: &mut String, s: &str) {
+        key.clear();
+        key.push_str(s);
+    }
+
+    fn push_key(&self, key: &String, path: &mut String, _scratch: &mut Vec<u8>) {
+        path.push_str(key);
+    }
+
+    fn error(&mut self, err: Error) -> Error {
+        let err = if err.kind() != ErrorKind::Syntax {
+            err
+        } else if self.failed {
+            Error::from(ErrorKind::Io)
+        } else if self.exceeded {
+            Error::from(ErrorKind::InputTooLong)
+        } else {
+            err
+        };
+        // Without reading any further, which could block, the input is known
+        // to have ended only if an attempt to read past it already failed.
+        let eof = self.eof && self.index == self.len;
+        let (offset, line, line_start) = self.position();
+        err.at_position(offset, line, offset - line_start, eof)
+    }
+
+    fn rewind_line(&mut self, offset: usize) -> bool {
+        let (_, _, line_start) = self.position();
+        if line_start <= offset {
+            return false;
+        }
+        // A line too long to have been kept in the window is resumed from
+        // where it was left off instead.
+        if line_start >= self.offset {
+            self.index = line_start - self.offset;
+        }
+        true
+    }
+}
+
+// Error for input that is not valid JSON. Whether this was caused by the input
+// ending prematurely is decided once the position is known.
+pub(crate) fn syntax() -> Error {
+    Error::from(ErrorKind::Syntax)
+}
+
+/// Parses a JSON escape sequence and appends it into the scratch space. Assumes
+/// the previous byte read was a backslash.
+fn parse_escape<'de, R: Read<'de> + ?Sized>(read: &mut R, scratch: &mut Vec<u8>) -> Result<()> {
+    let ch = next_or_eof(read)?;
+
+    match ch {
+        b'"' => scratch.push(b'"'),
+        b'\\' => scratch.push(b'\\'),
+        b'/' => scratch.push(b'/'),
+        b'b' => scratch.push(b'\x08'),
+        b'f' => scratch.push(b'\x0c'),
+        b'n' => scratch.push(b'\n'),
+        b'r' => scratch.push(b'\r'),
+        b't' => scratch.push(b'\t'),
+        b'u' => {
+            let c = match decode_hex_escape(read)? {
+                0xDC00..=0xDFFF => {
+                    return Err(syntax());
+                }
+
+                // Non-BMP characters are encoded as a sequence of
+                // two hex escapes, representing UTF-16 surrogates.
+                n1 @ 0xD800..=0xDBFF => {
+                    if next_or_eof(read)? != b'\\' {
+                        return Err(syntax());
+                    }
+                    if next_or_eof(read)? != b'u' {
+                        return Err(syntax());
+                    }
+
+                    let n2 = decode_hex_escape(read)?;
+
+                    if n2 < 0xDC00 || n2 > 0xDFFF {
+                        return Err(syntax());
+                    }
+
+                    let n = (u32::from(n1 - 0xD800) << 10 | u32::from(n2 - 0xDC00)) + 0x1_0000;
+
+                    match char::from_u32(n) {
+                        Some(c) => c,
+                        None => {
+                            return Err(syntax());
+                        }
+                    }
+                }
+
+                n => match char::from_u32(u32::from(n)) {
+                    Some(c) => c,
+                    None => {
+                        return Err(syntax());
+                    }
+                },
+            };
+
+            scratch.extend_from_slice(c.encode_utf8(&mut [0_u8; 4]).as_bytes());
+        }
+        _ => {
+            return Err(syntax());
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_hex_escape<'de, R: Read<'de> + ?Sized>(read: &mut R) -> Result<u16> {
+    let mut n = 0;
+    for _ in 0..4 {
+        n = match next_or_eof(read)? {
+            c @ b'0'..=b'9' => n * 16_u16 + u16::from(c - b'0'),
+            b'a' | b'A' => n * 16_u16 + 10_u16,
+            b'b' | b'B' => n * 16_u16 + 11_u16,
+            b'c' | b'C' => n * 16_u16 + 12_u16,
+            b'd' | b'D' => n * 16_u16 + 13_u16,
+            b'e' | b'E' => n * 16_u16 + 14_u16,
+            b'f' | b'F' => n * 16_u16 + 15_u16,
+            _ => {
+                return Err(syntax());
+            }
+        };
+    }
+    Ok(n)
+}
+
+fn next_or_eof<'de, R: Read<'de> + ?Sized>(read: &mut R) -> Result<u8> {
+    read.next().ok_or_else(syntax)
+}
+
+const CT: bool = true; // control character \x00..=\x1F
+const QU: bool = true; // quote \x22
+const BS: bool = true; // backslash \x5C
+const O: bool = false; // allow unescaped
+
+// Lookup table of bytes that must be escaped. A value of true at index i means
+// that byte i requires an escape sequence in the input.
+#[rustfmt::skip]
+static ESCAPE: [bool; 256] = [
+    //   1   2   3   4   5   6   7   8   9   A   B   C   D   E   F
+    CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, // 0
+    CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, // 1
+     O,  O, QU,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 2
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 3
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 4
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, BS,  O,  O,  O, // 5
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 6
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 7
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 8
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // 9
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // A
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // B
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // C
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // D
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // E
+     O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O,  O, // F
+];
@@ -0,0 +1,151 @@
+use super::de::Deserializer;
+use super::read::{Read, SliceRead};
+use super::Limits;
+use crate::de::{Deserialize, Visitor};
+use crate::error::{Error, Result};
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+
+#[cfg(feature = "std")]
+use super::read::IoRead;
+#[cfg(feature = "std")]
+use crate::de::DeserializeOwned;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Iterator that deserializes a stream of JSON values, such as the records of
+/// a [JSON Lines](https://jsonlines.org) file.
+///
+/// Values may be separated by any amount of whitespace, or by none where that
+/// is unambiguous, as in `[1][2]`.
+///
+/// Each call to `next` produces one value, or an error if that value was
+/// malformed or rejected by `T`. Following an error, the rest of the line on
+/// which the value began is skipped and the iterator carries on with the next
+/// line, so a single bad record in newline-delimited input does not stop the
+/// records after it from being read. If the error was only detected on a
+/// later line, as when a record is cut short, the iterator carries on from
+/// the start of the line where it was detected instead.
+///
+/// ```rust
+/// use miniserde::{json, Deserialize};
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Event {
+///     level: String,
+///     message: String,
+/// }
+///
+/// let j = r#"
+///     {"level": "info", "message": "starting up"}
+///     {"level": "warn", "message": "disk almost full"}
+/// "#;
+///
+/// let mut stream = json::StreamDeserializer::<Event>::from_str(j);
+/// while let Some(event) = stream.next() {
+///     let event = event?;
+///     println!("{:?} at byte {}", event, stream.offset());
+/// }
+/// # Ok::<(), miniserde::Error>(())
+/// ```
+pub struct StreamDeserializer<'a, T> {
+    values: Box<dyn Values<'a> + 'a>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Deserialize<'a>> StreamDeserializer<'a, T> {
+    /// Deserialize the values in a string.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(j: &'a str) -> Self {
+        StreamDeserializer::new(SliceRead::utf8(j))
+    }
+
+    /// Deserialize the values in a byte slice, which is checked for UTF-8
+    /// only inside of string literals as in
+    /// [`json::from_slice`][crate::json::from_slice].
+    pub fn from_slice(j: &'a [u8]) -> Self {
+        StreamDeserializer::new(SliceRead::new(j))
+    }
+
+    fn new<R: Read<'a> + 'a>(read: R) -> Self {
+        StreamDeserializer::with_limits(read, &Limits::new())
+    }
+
+    pub(super) fn with_limits<R: Read<'a> + 'a>(read: R, limits: &Limits) -> Self {
+        StreamDeserializer {
+            values: Box::new(Stream {
+                de: Deserializer::new(read, limits),
+                offset: 0,
+                skip: false,
+            }),
+            marker: PhantomData,
+        }
+    }
+
+    /// Byte offset in the input at which the value most recently returned by
+    /// `next` began, whether or not it was successfully deserialized.
+    pub fn offset(&self) -> usize {
+        self.values.offset()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T: DeserializeOwned> StreamDeserializer<'a, T> {
+    /// Deserialize the values read from an `io::Read`, as they are read,
+    /// through the same fixed-size buffer as
+    /// [`json::from_reader`][crate::json::from_reader].
+    pub fn from_reader<R: io::Read + 'a>(reader: R) -> Self {
+        StreamDeserializer::new(IoRead::new(reader))
+    }
+}
+
+impl<'a, T: Deserialize<'a>> Iterator for StreamDeserializer<'a, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut out = None;
+        Some(match self.values.next(T::begin(&mut out))? {
+            Ok(()) => out.ok_or(Error),
+            Err(err) => Err(err),
+        })
+    }
+}
+
+// Object-safe view of a Stream, so that StreamDeserializer does not need to
+// name the type of its input.
+trait Values<'de> {
+    fn next(&mut self, visitor: &mut dyn Visitor<'de>) -> Option<Result<()>>;
+    fn offset(&self) -> usize;
+}
+
+struct Stream<'de, R: Read<'de>> {
+    // Nothing is left on the deserializer's stack in between values, even if
+    // a visitor panicked, which is what makes it possible to keep it around
+    // from one value to the next even though each value's builders borrow
+    // from a different output.
+    de: Deserializer<'de, R>,
+    offset: usize,
+    // Whether the previous value failed or panicked, and the input needs to
+    // be skipped up to the line where the next value may begin.
+    skip: bool,
+}
+
+impl<'de, R: Read<'de>> Values<'de> for Stream<'de, R> {
+    fn next(&mut self, visitor: &mut dyn Visitor<'de>) -> Option<Result<()>> {
+        if self.skip {
+            self.de.skip_line(self.offset);
+            self.skip = false;
+        }
+        let (offset, first) = self.de.begin_value()?;
+        self.offset = offset;
+        self.skip = true;
+        let result = self.de.value(visitor);
+        let result = result.and_then(|()| self.de.end_value(first));
+        self.skip = result.is_err();
+        Some(result)
+    }
+
+    fn offset(&self) -> usize {
+        self.offset
+    }
+}
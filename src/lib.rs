@@ -61,6 +61,19 @@
 //! to parse JSON into any Deserialize type. Like serde\_json there is a `Value`
 //! enum for embedding untyped components.
 //!
+//! ## <font color="#C0C0C0">Similar:</font> Serde's JSON representation
+//!
+//! The miniserde derive macros accept structs and enums and represent them in
+//! JSON the way Serde does. Braced structs are objects, tuple structs are
+//! arrays, newtype structs are the value they wrap, and unit structs are null.
+//! Enum variants carrying data are represented by default as
+//! `{"Variant": ...}`. The other representations are available too:
+//! `#[serde(tag = "t")]` gives
+//! `{"t": "Variant", ...}`, `#[serde(tag = "t", content = "c")]` gives
+//! `{"t": "Variant", "c": ...}`, and `#[serde(untagged)]` gives the data
+//! alone. An enum of unit variants with `#[serde(repr = "int")]` is
+//! represented by the numeric discriminant of each variant.
+//!
 //! ## <font color="#C0C0C0">Different:</font> Minimal design
 //!
 //! This library does not tackle as expansive of a range of use cases as Serde
@@ -94,15 +107,19 @@
 //! stack overflows. Not even the Drop impl of our json `Value` type is
 //! recursive so you can safely nest them arbitrarily.
 //!
-//! ## <font color="#C0C0C0">Different:</font> No deserialization error messages
+//! ## <font color="#C0C0C0">Different:</font> Minimal deserialization error messages
 //!
-//! When deserialization fails, the error type is a unit struct containing no
-//! information. This is a legit strategy and not just laziness. If your use
-//! case does not require error messages, good, you save on compiling and having
-//! your instruction cache polluted by error handling code. If you do need error
-//! messages, then upon error you can pass the same input to serde\_json to
-//! receive a line, column, and helpful description of the failure. This keeps
-//! error handling logic out of caches along the performance-critical codepath.
+//! When deserialization fails, the error tells you only the broad kind of
+//! failure, the line and column at which it was detected, and the path to the
+//! offending value such as `.users[3].address`. Where they apply, it also names
+//! the field and the containing struct, or carries the message of a failed
+//! `try_from` conversion. There is no description of what was expected versus
+//! found. This is a legit strategy and not just laziness. The error is
+//! assembled only after parsing has already failed, so you save on compiling
+//! and having your instruction cache polluted by error handling code. If you
+//! need a helpful description of the failure, then upon error you can pass the
+//! same input to serde\_json. This keeps error handling logic out of caches
+//! along the performance-critical codepath.
 //!
 //! ## <font color="#C0C0C0">Different:</font> Infallible serialization
 //!
@@ -117,24 +134,56 @@
 //! formats, but it is not a goal to enable that through what this library
 //! exposes.
 //!
-//! ## <font color="#C0C0C0">Different:</font> Structs only
-//!
-//! The miniserde derive macros will refuse anything other than a braced struct
-//! with named fields. Enums and tuple structs are not supported.
-//!
-//! ## <font color="#C0C0C0">Different:</font> No customization
+//! ## <font color="#C0C0C0">Different:</font> Serde's common attributes only
 //!
 //! Serde has tons of knobs for configuring the derived serialization and
 //! deserialization logic through attributes. Or for the ultimate level of
 //! configurability you can handwrite arbitrarily complicated implementations of
 //! its traits.
 //!
-//! Miniserde provides just one attribute which is `rename`, and severely
-//! restricts the kinds of on-the-fly manipulation that are possible in custom
-//! impls. If you need any of this, use Serde -- it's a great library.
+//! Miniserde's derive macros accept the `#[serde(...)]` attributes that most
+//! code reaches for, with the same meaning as in Serde, and reject any other:
+//!
+//! - on a struct or enum: `rename_all`, `default`, `deny_unknown_fields`,
+//!   `transparent`, `from`, `try_from`, `into`, `tag`, `content`, `untagged`,
+//!   and miniserde's own `repr = "int"`;
+//! - on a field: `rename`, `alias`, `default`, `flatten`, `skip`,
+//!   `skip_serializing`, `skip_deserializing`, `skip_serializing_if`, `with`,
+//!   `serialize_with` and `deserialize_with`;
+//! - on a variant: `rename`, `alias` and `other`.
+//!
+//! Miniserde also severely restricts the kinds of on-the-fly manipulation that
+//! are possible in custom impls. If you need more than this, use Serde -- it's
+//! a great library.
+//!
+//! ## Upgrading from 0.1
+//!
+//! Deserialization now lets strings be borrowed from the input, which puts the
+//! input's lifetime `'de` on the deserialization traits. Derived impls need no
+//! changes. Handwritten ones change as follows:
+//!
+//! - `impl Deserialize for T` becomes `impl<'de> Deserialize<'de> for T`, and
+//!   `begin` returns `&mut dyn Visitor<'de>`.
+//! - `impl Visitor for Place<T>` becomes `impl<'de> Visitor<'de> for Place<T>`.
+//!   `seq` and `map` return `Box<dyn Seq<'de> + 'a>` and `Box<dyn Map<'de> + 'a>`
+//!   and add `where 'de: 'a`.
+//! - `Seq` and `Map` become `Seq<'de>` and `Map<'de>`, and `element` and `key`
+//!   return `&mut dyn Visitor<'de>`.
+//! - Generic code that deserializes from an `io::Read`, which cannot lend out
+//!   strings, bounds its type by `DeserializeOwned` instead of `Deserialize`.
+//!
+//! `miniserde::Error` now carries the kind and location of the failure. It is no
+//! longer `Copy`. The constant `miniserde::Error` is still there to reject input
+//! from a handwritten impl.
 
-#![doc(html_root_url = "https://docs.rs/miniserde/0.1.23")]
+#![doc(html_root_url = "https://docs.rs/miniserde/0.2.0")]
 #![allow(
+    // Lints that are newer than the oldest supported compiler. Place impls
+    // are written inside of the function that hands out the place, and the
+    // lifetime of a Fragment or Cow is left elided throughout.
+    unknown_lints,
+    mismatched_lifetime_syntaxes,
+    non_local_definitions,
     clippy::needless_doctest_main,
     clippy::vec_init_then_push,
     // Regression causing false positives:
@@ -150,7 +199,6 @@
     clippy::checked_conversions,
     clippy::doc_markdown,
     clippy::enum_glob_use,
-    clippy::let_underscore_drop,
     clippy::missing_errors_doc,
     clippy::must_use_candidate,
     clippy::redundant_else,
@@ -182,9 +230,13 @@ mod careful;
 #[macro_use]
 mod place;
 
+mod convert;
 mod error;
+mod flatten;
 mod ignore;
 mod ptr;
+mod variant;
+mod with;
 
 pub mod de;
 pub mod json;
@@ -192,7 +244,7 @@ pub mod ser;
 
 #[doc(inline)]
 pub use crate::de::Deserialize;
-pub use crate::error::{Error, Result};
+pub use crate::error::{Error, ErrorKind, Result};
 #[doc(inline)]
 pub use crate::ser::Serialize;
 
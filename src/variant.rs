@@ -0,0 +1,311 @@
+// Support for enums with data-carrying variants in derived impls.
+
+use crate::de::{self, replay, Deserialize, Visitor};
+use crate::error::{Error, Result};
+use crate::ser::{self, Fragment, Serialize};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::mem;
+
+// An externally tagged variant, which is a map whose only key is the name of
+// the variant.
+pub fn variant<'a>(name: &'static str, content: Fragment<'a>) -> Fragment<'a> {
+    Fragment::Map(Box::new(Variant {
+        name,
+        content: Content(Cell::new(Some(content))),
+        done: false,
+    }))
+}
+
+struct Variant<'a> {
+    name: &'static str,
+    content: Content<'a>,
+    done: bool,
+}
+
+// Hands out the already begun fragment of the variant's data. The serializer
+// begins each value exactly once.
+struct Content<'a>(Cell<Option<Fragment<'a>>>);
+
+impl<'a> ser::Map for Variant<'a> {
+    fn next(&mut self) -> Option<(Cow<str>, &dyn Serialize)> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        Some((Cow::Borrowed(self.name), &self.content))
+    }
+}
+
+impl<'a> Serialize for Content<'a> {
+    fn begin(&self) -> Fragment {
+        self.0.take().unwrap_or(Fragment::Null)
+    }
+}
+
+// An adjacently tagged variant, which is a map holding the variant name under
+// the key `tag` and its data, if any, under the key `content`.
+pub fn adjacent_variant<'a>(
+    tag: &'static str,
+    content_key: &'static str,
+    name: &'a str,
+    content: Option<Fragment<'a>>,
+) -> Fragment<'a> {
+    Fragment::Map(Box::new(Adjacent {
+        tag,
+        content_key,
+        name,
+        content: content.map(|content| Content(Cell::new(Some(content)))),
+        state: 0,
+    }))
+}
+
+struct Adjacent<'a> {
+    tag: &'static str,
+    content_key: &'static str,
+    name: &'a str,
+    content: Option<Content<'a>>,
+    state: usize,
+}
+
+impl<'a> ser::Map for Adjacent<'a> {
+    fn next(&mut self) -> Option<(Cow<str>, &dyn Serialize)> {
+        let state = self.state;
+        self.state = state + 1;
+        match state {
+            0 => Some((Cow::Borrowed(self.tag), &self.name)),
+            1 => match &self.content {
+                Some(content) => Some((Cow::Borrowed(self.content_key), content)),
+                None => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+// The discriminant of a variant of an enum with #[serde(repr = "int")].
+pub fn discriminant(n: i128) -> Fragment<'static> {
+    if n < 0 {
+        Fragment::I64(n as i64)
+    } else {
+        Fragment::U64(n as u64)
+    }
+}
+
+// Implemented by the visitor of a derived enum to find where the data of the
+// variant with the given name goes. A unit variant is written immediately and
+// has no visitor for its data.
+pub trait Variants<'de> {
+    fn variant(&mut self, name: &str) -> Result<Option<&mut dyn Visitor<'de>>>;
+
+    // Whether the variant with the given name has no use for its data, like a
+    // #[serde(other)] variant, so that the data may be left out altogether.
+    fn ignores_data(&self, name: &str) -> bool {
+        let _ = name;
+        false
+    }
+
+    // Called once the variant's data has been deserialized, for variants whose
+    // data is held aside until then.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// An internally tagged enum is a map holding the variant name under the key
+// `tag` alongside the fields of the variant. Any fields that come before the
+// tag are buffered, then replayed into the variant once it is known.
+pub fn internally_tagged<'a, 'de>(
+    tag: &'static str,
+    container: &'static str,
+    variants: &'a mut dyn Variants<'de>,
+) -> Result<Box<dyn de::Map<'de> + 'a>> {
+    Ok(Box::new(InternallyTagged {
+        tag,
+        container,
+        name: None,
+        buffer: Vec::new(),
+        state: State::Pending(variants),
+    }))
+}
+
+struct InternallyTagged<'a, 'de> {
+    tag: &'static str,
+    container: &'static str,
+    name: Option<String>,
+    buffer: Vec<(String, Option<de::content::Content<'de>>)>,
+    state: State<'a, 'de>,
+}
+
+enum State<'a, 'de> {
+    Pending(&'a mut dyn Variants<'de>),
+    Content(Box<dyn de::Map<'de> + 'a>),
+    Unit,
+}
+
+impl<'a, 'de> InternallyTagged<'a, 'de> {
+    // Once the tag has been read, pick the variant and catch it up on any
+    // fields seen so far.
+    fn dispatch(&mut self) -> Result<()> {
+        let name = match &self.name {
+            Some(name) => name,
+            None => return Ok(()),
+        };
+        let variants = match mem::replace(&mut self.state, State::Unit) {
+            State::Pending(variants) => variants,
+            state => {
+                self.state = state;
+                return Ok(());
+            }
+        };
+        let visitor = match variants.variant(name)? {
+            Some(visitor) => visitor,
+            None => return Ok(()),
+        };
+        let mut map = visitor.map()?;
+        for (k, v) in mem::replace(&mut self.buffer, Vec::new()) {
+            if let Some(v) = v {
+                replay::replay_value(&v, map.key(&k)?)?;
+            }
+        }
+        self.state = State::Content(map);
+        Ok(())
+    }
+}
+
+impl<'a, 'de> de::Map<'de> for InternallyTagged<'a, 'de> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        self.dispatch()?;
+        if k == self.tag {
+            if self.name.is_some() {
+                return Err(Error);
+            }
+            return Ok(Deserialize::begin(&mut self.name));
+        }
+        match &mut self.state {
+            State::Pending(_) => {
+                self.buffer.push((k.to_owned(), None));
+                Ok(Deserialize::begin(&mut self.buffer.last_mut().unwrap().1))
+            }
+            State::Content(map) => map.key(k),
+            State::Unit => Ok(<dyn Visitor>::ignore()),
+        }
+    }
+
+    fn __flatten_key(&mut self, k: &str) -> Result<Option<&mut dyn Visitor<'de>>> {
+        self.dispatch()?;
+        let pending = match self.state {
+            State::Pending(_) => true,
+            State::Content(_) | State::Unit => false,
+        };
+        if k == self.tag || pending {
+            return self.key(k).map(Some);
+        }
+        match &mut self.state {
+            State::Content(map) => map.__flatten_key(k),
+            State::Pending(_) | State::Unit => Ok(None),
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.dispatch()?;
+        match &mut self.state {
+            State::Pending(_) => Err(Error::missing_field(self.tag, self.container)),
+            State::Content(map) => map.finish(),
+            State::Unit => Ok(()),
+        }
+    }
+}
+
+// An adjacently tagged enum is a map holding the variant name under the key
+// `tag` and the data of the variant under the key `content`. If the data comes
+// before the tag, it is buffered and replayed once the tag has been read.
+pub fn adjacently_tagged<'a, 'de>(
+    tag: &'static str,
+    content_key: &'static str,
+    container: &'static str,
+    variants: Box<dyn Variants<'de> + 'a>,
+) -> Result<Box<dyn de::Map<'de> + 'a>>
+where
+    'de: 'a,
+{
+    Ok(Box::new(AdjacentlyTagged {
+        tag,
+        content_key,
+        container,
+        name: None,
+        buffer: None,
+        seen: false,
+        unit: None,
+        variants,
+    }))
+}
+
+struct AdjacentlyTagged<'a, 'de> {
+    tag: &'static str,
+    content_key: &'static str,
+    container: &'static str,
+    name: Option<String>,
+    buffer: Option<de::content::Content<'de>>,
+    seen: bool,
+    unit: Option<()>,
+    variants: Box<dyn Variants<'de> + 'a>,
+}
+
+impl<'a, 'de> de::Map<'de> for AdjacentlyTagged<'a, 'de> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        if k == self.tag {
+            if self.name.is_some() {
+                return Err(Error);
+            }
+            return Ok(Deserialize::begin(&mut self.name));
+        }
+        if k != self.content_key {
+            return Ok(<dyn Visitor>::ignore());
+        }
+        if self.seen {
+            return Err(Error);
+        }
+        self.seen = true;
+        match &self.name {
+            Some(name) => match self.variants.variant(name)? {
+                Some(visitor) => Ok(visitor),
+                // The data of a unit variant, if present at all, is null.
+                None => Ok(Deserialize::begin(&mut self.unit)),
+            },
+            None => Ok(Deserialize::begin(&mut self.buffer)),
+        }
+    }
+
+    fn __flatten_key(&mut self, k: &str) -> Result<Option<&mut dyn Visitor<'de>>> {
+        if k == self.tag || k == self.content_key {
+            self.key(k).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let name = match &self.name {
+            Some(name) => name,
+            None => return Err(Error::missing_field(self.tag, self.container)),
+        };
+        if let Some(value) = self.buffer.take() {
+            match self.variants.variant(name)? {
+                Some(visitor) => replay::replay_value(&value, visitor)?,
+                None => replay::replay_value(&value, Deserialize::begin(&mut self.unit))?,
+            }
+        } else if !self.seen {
+            // Only a unit variant, or one whose data would be ignored anyway,
+            // may leave out the content.
+            let ignores_data = self.variants.ignores_data(name);
+            if self.variants.variant(name)?.is_some() && !ignores_data {
+                return Err(Error::missing_field(self.content_key, self.container));
+            }
+        }
+        self.variants.finish()
+    }
+}
@@ -0,0 +1,23 @@
+// Support for #[serde(serialize_with = "...")] in derived impls.
+
+use crate::ser::{Fragment, Serialize};
+use core::cell::Cell;
+
+// Holds the fragment returned by a field's serialize_with function until the
+// serializer begins it. A derived ser::Map keeps one of these and refills it
+// for each field that has a custom serializer.
+#[derive(Default)]
+pub struct SerializeWith<'a>(Cell<Option<Fragment<'a>>>);
+
+impl<'a> SerializeWith<'a> {
+    pub fn set(&mut self, fragment: Fragment<'a>) -> &dyn Serialize {
+        self.0.set(Some(fragment));
+        self
+    }
+}
+
+impl<'a> Serialize for SerializeWith<'a> {
+    fn begin(&self) -> Fragment {
+        self.0.take().unwrap_or(Fragment::Null)
+    }
+}
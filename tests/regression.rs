@@ -1,3 +1,6 @@
+// Regression tests define types only to deserialize into them, without
+// reading their fields.
+#[allow(dead_code)]
 mod regression {
     automod::dir!("tests/regression");
 }
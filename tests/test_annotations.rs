@@ -0,0 +1,693 @@
+use miniserde::json::{self, Object, Value};
+use miniserde::{Deserialize, ErrorKind, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct FieldDefault {
+    a: u32,
+    #[serde(default)]
+    b: Vec<u32>,
+    #[serde(default = "default_c")]
+    c: String,
+    #[serde(default)]
+    d: Option<u32>,
+}
+
+fn default_c() -> String {
+    "c".to_owned()
+}
+
+#[test]
+fn test_field_default() {
+    let j = r#"{"a": 1}"#;
+    let actual: FieldDefault = json::from_str(j).unwrap();
+    let expected = FieldDefault {
+        a: 1,
+        b: Vec::new(),
+        c: "c".to_owned(),
+        d: None,
+    };
+    assert_eq!(actual, expected);
+
+    let j = r#"{"a": 1, "b": [2], "c": "C", "d": 4}"#;
+    let actual: FieldDefault = json::from_str(j).unwrap();
+    let expected = FieldDefault {
+        a: 1,
+        b: vec![2],
+        c: "C".to_owned(),
+        d: Some(4),
+    };
+    assert_eq!(actual, expected);
+
+    let err = json::from_str::<FieldDefault>("{}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.field(), Some("a"));
+}
+
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(default)]
+struct ContainerDefault {
+    a: u32,
+    b: Option<String>,
+    #[serde(default = "default_c")]
+    c: String,
+}
+
+impl Default for ContainerDefault {
+    fn default() -> Self {
+        ContainerDefault {
+            a: 7,
+            b: Some("b".to_owned()),
+            c: String::new(),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(default = "ContainerDefaultPath::new")]
+struct ContainerDefaultPath {
+    x: u8,
+    y: u8,
+}
+
+impl ContainerDefaultPath {
+    fn new() -> Self {
+        ContainerDefaultPath { x: 1, y: 2 }
+    }
+}
+
+#[test]
+fn test_container_default() {
+    let actual: ContainerDefault = json::from_str("{}").unwrap();
+    let expected = ContainerDefault {
+        a: 7,
+        b: Some("b".to_owned()),
+        c: "c".to_owned(),
+    };
+    assert_eq!(actual, expected);
+
+    let actual: ContainerDefault = json::from_str(r#"{"a": 0, "b": null}"#).unwrap();
+    let expected = ContainerDefault {
+        a: 0,
+        b: None,
+        c: "c".to_owned(),
+    };
+    assert_eq!(actual, expected);
+
+    let actual: ContainerDefaultPath = json::from_str(r#"{"y": 0}"#).unwrap();
+    assert_eq!(actual, ContainerDefaultPath { x: 1, y: 0 });
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Skip {
+    a: u32,
+    #[serde(skip)]
+    cache: Vec<u32>,
+    #[serde(skip_serializing)]
+    write_only: String,
+    #[serde(skip_deserializing)]
+    read_only: u32,
+    #[serde(skip, default = "default_c")]
+    handle: String,
+}
+
+#[test]
+fn test_skip() {
+    let skip = Skip {
+        a: 1,
+        cache: vec![1, 2, 3],
+        write_only: "secret".to_owned(),
+        read_only: 2,
+        handle: "h".to_owned(),
+    };
+    let j = json::to_string(&skip);
+    assert_eq!(j, r#"{"a":1,"read_only":2}"#);
+
+    let j = r#"{"a": 1, "cache": [4], "write_only": "w", "read_only": 3, "handle": "x"}"#;
+    let actual: Skip = json::from_str(j).unwrap();
+    let expected = Skip {
+        a: 1,
+        cache: Vec::new(),
+        write_only: "w".to_owned(),
+        read_only: 0,
+        handle: "c".to_owned(),
+    };
+    assert_eq!(actual, expected);
+
+    let err = json::from_str::<Skip>(r#"{"a": 1}"#).unwrap_err();
+    assert_eq!(err.field(), Some("write_only"));
+}
+
+#[derive(PartialEq, Debug, Serialize)]
+struct SkipIf {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    a: Option<u32>,
+    b: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    c: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    d: Vec<u32>,
+}
+
+#[test]
+fn test_skip_serializing_if() {
+    let value = SkipIf {
+        a: None,
+        b: 1,
+        c: None,
+        d: Vec::new(),
+    };
+    assert_eq!(json::to_string(&value), r#"{"b":1}"#);
+
+    let value = SkipIf {
+        a: Some(0),
+        b: 1,
+        c: Some(2),
+        d: vec![3],
+    };
+    assert_eq!(json::to_string(&value), r#"{"a":0,"b":1,"c":2,"d":[3]}"#);
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RenameAllFields {
+    user_id: u32,
+    #[serde(rename = "KIND")]
+    account_kind: RenameAllVariants,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+enum RenameAllVariants {
+    PowerUser,
+    #[serde(rename = "guest")]
+    Guest,
+}
+
+#[test]
+fn test_rename_all() {
+    let value = RenameAllFields {
+        user_id: 1,
+        account_kind: RenameAllVariants::PowerUser,
+    };
+    let j = json::to_string(&value);
+    assert_eq!(j, r#"{"userId":1,"KIND":"POWER-USER"}"#);
+    assert_eq!(json::from_str::<RenameAllFields>(&j).unwrap(), value);
+
+    let j = r#"{"userId": 2, "KIND": "guest"}"#;
+    let expected = RenameAllFields {
+        user_id: 2,
+        account_kind: RenameAllVariants::Guest,
+    };
+    assert_eq!(json::from_str::<RenameAllFields>(j).unwrap(), expected);
+}
+
+macro_rules! rename_all {
+    ($($rule:tt => $field:tt $variant:tt,)*) => {$({
+        #[derive(Serialize)]
+        #[serde(rename_all = $rule)]
+        struct Struct {
+            field_name: (),
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = $rule)]
+        enum Enum {
+            VariantName,
+        }
+
+        let j = json::to_string(&Struct { field_name: () });
+        assert_eq!(j, concat!("{\"", $field, "\":null}"));
+        let j = json::to_string(&Enum::VariantName);
+        assert_eq!(j, concat!("\"", $variant, "\""));
+    })*};
+}
+
+#[test]
+fn test_rename_all_rules() {
+    rename_all! {
+        "lowercase" => "field_name" "variantname",
+        "UPPERCASE" => "FIELD_NAME" "VARIANTNAME",
+        "PascalCase" => "FieldName" "VariantName",
+        "camelCase" => "fieldName" "variantName",
+        "snake_case" => "field_name" "variant_name",
+        "SCREAMING_SNAKE_CASE" => "FIELD_NAME" "VARIANT_NAME",
+        "kebab-case" => "field-name" "variant-name",
+        "SCREAMING-KEBAB-CASE" => "FIELD-NAME" "VARIANT-NAME",
+    }
+}
+
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DenyUnknown {
+    timeout_ms: u32,
+    #[serde(skip_deserializing)]
+    skipped: u32,
+}
+
+#[test]
+fn test_deny_unknown_fields() {
+    let j = r#"{"timeout_ms": 1}"#;
+    let expected = DenyUnknown {
+        timeout_ms: 1,
+        skipped: 0,
+    };
+    assert_eq!(json::from_str::<DenyUnknown>(j).unwrap(), expected);
+
+    let j = r#"{"timout_ms": 1}"#;
+    let err = json::from_str::<DenyUnknown>(j).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnknownField);
+    assert_eq!(err.field(), Some("timout_ms"));
+    assert_eq!(err.container(), Some("DenyUnknown"));
+    assert_eq!(err.path(), Some(".timout_ms"));
+
+    let j = r#"{"timeout_ms": 1, "skipped": 1}"#;
+    let err = json::from_str::<DenyUnknown>(j).unwrap_err();
+    assert_eq!(err.field(), Some("skipped"));
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Alias {
+    #[serde(alias = "timeout", alias = "timeout_secs")]
+    timeout_ms: u32,
+    mode: AliasMode,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+enum AliasMode {
+    #[serde(rename = "fast", alias = "Quick")]
+    Fast,
+    Slow,
+}
+
+#[test]
+fn test_alias() {
+    let expected = Alias {
+        timeout_ms: 1,
+        mode: AliasMode::Fast,
+    };
+    for j in &[
+        r#"{"timeout_ms": 1, "mode": "fast"}"#,
+        r#"{"timeout": 1, "mode": "Quick"}"#,
+        r#"{"timeout_secs": 1, "mode": "fast"}"#,
+    ] {
+        assert_eq!(json::from_str::<Alias>(j).unwrap(), expected);
+    }
+    assert_eq!(
+        json::to_string(&expected),
+        r#"{"timeout_ms":1,"mode":"fast"}"#
+    );
+}
+
+#[derive(Serialize, Deserialize)]
+struct Flatten {
+    id: u32,
+    #[serde(flatten)]
+    pagination: Pagination,
+    #[serde(flatten)]
+    extra: BTreeMap<String, Value>,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Pagination {
+    limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u32>,
+}
+
+#[test]
+fn test_flatten() {
+    let j = r#"{"limit": 10, "id": 1, "nested": {"a": [true, null]}, "offset": 20}"#;
+    let actual: Flatten = json::from_str(j).unwrap();
+    assert_eq!(actual.id, 1);
+    assert_eq!(
+        actual.pagination,
+        Pagination {
+            limit: 10,
+            offset: Some(20),
+        },
+    );
+    assert_eq!(actual.extra.len(), 1);
+    assert_eq!(
+        json::to_string(&actual.extra["nested"]),
+        r#"{"a":[true,null]}"#
+    );
+    assert_eq!(
+        json::to_string(&actual),
+        r#"{"id":1,"limit":10,"offset":20,"nested":{"a":[true,null]}}"#
+    );
+
+    let actual = Flatten {
+        id: 1,
+        pagination: Pagination {
+            limit: 10,
+            offset: None,
+        },
+        extra: BTreeMap::new(),
+    };
+    assert_eq!(json::to_string(&actual), r#"{"id":1,"limit":10}"#);
+
+    let j = r#"{"id": 1, "offset": 20}"#;
+    let err = json::from_str::<Flatten>(j).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.field(), Some("limit"));
+    assert_eq!(err.container(), Some("Pagination"));
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct FlattenOption {
+    id: u32,
+    #[serde(flatten)]
+    pagination: Option<Pagination>,
+}
+
+#[test]
+fn test_flatten_option() {
+    let cases = vec![
+        (
+            FlattenOption {
+                id: 1,
+                pagination: None,
+            },
+            r#"{"id":1}"#,
+        ),
+        (
+            FlattenOption {
+                id: 1,
+                pagination: Some(Pagination {
+                    limit: 10,
+                    offset: None,
+                }),
+            },
+            r#"{"id":1,"limit":10}"#,
+        ),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<FlattenOption>(j).unwrap(), value);
+    }
+
+    // Without the required "limit", the keys are no Pagination.
+    let j = r#"{"id": 1, "offset": 20}"#;
+    let actual = json::from_str::<FlattenOption>(j).unwrap();
+    assert_eq!(actual.pagination, None);
+}
+
+#[derive(Deserialize)]
+struct FlattenForwarded {
+    #[serde(flatten)]
+    pagination: Box<Pagination>,
+    #[serde(flatten)]
+    shape: Shape,
+    #[serde(flatten)]
+    extra: BTreeMap<String, Value>,
+}
+
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(tag = "shape")]
+enum Shape {
+    Square { side: u32 },
+}
+
+#[test]
+fn test_flatten_forwarded() {
+    // Keys that the boxed struct and the tagged enum have no use for are left
+    // over for the map after them. Buffered keys are replayed in sorted order,
+    // and the enum holds on to every key until it has seen the tag, so only
+    // keys that sort after the tag can be left over by it.
+    let j = r#"{"limit": 10, "side": 2, "shape": "Square", "zone": "red"}"#;
+    let actual: FlattenForwarded = json::from_str(j).unwrap();
+    assert_eq!(actual.pagination.limit, 10);
+    assert_eq!(actual.shape, Shape::Square { side: 2 });
+    assert_eq!(actual.extra.keys().collect::<Vec<_>>(), ["zone"]);
+}
+
+#[derive(Serialize, Deserialize)]
+struct FlattenObject {
+    id: u32,
+    #[serde(flatten)]
+    rest: Object,
+}
+
+#[test]
+fn test_flatten_object() {
+    let j = r#"{"id": 1, "b": 2, "a": {"c": [3]}}"#;
+    let actual: FlattenObject = json::from_str(j).unwrap();
+    assert_eq!(actual.id, 1);
+    assert_eq!(json::to_string(&actual), r#"{"id":1,"a":{"c":[3]},"b":2}"#);
+}
+
+mod millis {
+    use miniserde::de::Visitor;
+    use miniserde::ser::Fragment;
+    use miniserde::{make_place, Result};
+    use std::time::Duration;
+
+    make_place!(Place);
+
+    pub fn serialize(duration: &Duration) -> Fragment<'static> {
+        Fragment::U64(duration.as_secs() * 1000 + u64::from(duration.subsec_millis()))
+    }
+
+    impl<'de> Visitor<'de> for Place<Duration> {
+        fn nonnegative(&mut self, n: u64) -> Result<()> {
+            self.out = Some(Duration::from_millis(n));
+            Ok(())
+        }
+    }
+
+    pub fn deserialize<'de>(out: &mut Option<Duration>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+// Accepts a number either as is or as a string.
+fn lenient<'de>(out: &mut Option<u32>) -> &mut dyn miniserde::de::Visitor<'de> {
+    use miniserde::de::Visitor;
+    use miniserde::{make_place, Error, Result};
+
+    make_place!(Place);
+
+    impl<'de> Visitor<'de> for Place<u32> {
+        fn nonnegative(&mut self, n: u64) -> Result<()> {
+            self.out = Some(n as u32);
+            Ok(())
+        }
+
+        fn string(&mut self, s: &str) -> Result<()> {
+            self.out = Some(s.parse().map_err(|_| Error)?);
+            Ok(())
+        }
+    }
+
+    Place::new(out)
+}
+
+fn hex(bytes: &[u8]) -> miniserde::ser::Fragment<'static> {
+    let hex = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    miniserde::ser::Fragment::Str(std::borrow::Cow::Owned(hex))
+}
+
+#[test]
+fn test_with() {
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Request {
+        #[serde(with = "millis")]
+        timeout: Duration,
+        #[serde(serialize_with = "hex")]
+        payload: Vec<u8>,
+        #[serde(deserialize_with = "lenient", default)]
+        retries: u32,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Event {
+        Wait {
+            #[serde(with = "millis")]
+            duration: Duration,
+            label: String,
+        },
+    }
+
+    let request = Request {
+        timeout: Duration::from_millis(1500),
+        payload: vec![0, 255, 16],
+        retries: 2,
+    };
+    let j = r#"{"timeout":1500,"payload":"00ff10","retries":2}"#;
+    assert_eq!(json::to_string(&request), j);
+
+    let j = r#"{"timeout": 250, "payload": [1, 2], "retries": "5"}"#;
+    let expected = Request {
+        timeout: Duration::from_millis(250),
+        payload: vec![1, 2],
+        retries: 5,
+    };
+    assert_eq!(json::from_str::<Request>(j).unwrap(), expected);
+
+    let j = r#"{"timeout": 250, "payload": []}"#;
+    let expected = Request {
+        timeout: Duration::from_millis(250),
+        payload: Vec::new(),
+        retries: 0,
+    };
+    assert_eq!(json::from_str::<Request>(j).unwrap(), expected);
+
+    let err = json::from_str::<Request>(r#"{"payload": []}"#)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.field(), Some("timeout"));
+    assert!(json::from_str::<Request>(r#"{"timeout": "1s", "payload": []}"#).is_err());
+
+    let event = Event::Wait {
+        duration: Duration::from_millis(20),
+        label: "w".to_owned(),
+    };
+    let j = r#"{"Wait":{"duration":20,"label":"w"}}"#;
+    assert_eq!(json::to_string(&event), j);
+    assert_eq!(json::from_str::<Event>(j).unwrap(), event);
+}
+
+#[test]
+fn test_conversions() {
+    use std::convert::TryFrom;
+
+    #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+    #[serde(try_from = "String", into = "String")]
+    struct Email(String);
+
+    impl TryFrom<String> for Email {
+        type Error = &'static str;
+
+        fn try_from(s: String) -> Result<Self, Self::Error> {
+            if s.contains('@') {
+                Ok(Email(s))
+            } else {
+                Err("missing @")
+            }
+        }
+    }
+
+    impl From<Email> for String {
+        fn from(email: Email) -> Self {
+            email.0
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+    struct RawRange {
+        start: u16,
+        end: u16,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+    #[serde(from = "RawRange", into = "RawRange")]
+    struct Range {
+        len: u16,
+        first: u16,
+    }
+
+    impl From<RawRange> for Range {
+        fn from(raw: RawRange) -> Self {
+            Range {
+                first: raw.start,
+                len: raw.end - raw.start,
+            }
+        }
+    }
+
+    impl From<Range> for RawRange {
+        fn from(range: Range) -> Self {
+            RawRange {
+                start: range.first,
+                end: range.first + range.len,
+            }
+        }
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    #[serde(try_from = "Vec<u16>")]
+    struct Ports(Vec<u16>);
+
+    impl TryFrom<Vec<u16>> for Ports {
+        type Error = String;
+
+        fn try_from(ports: Vec<u16>) -> Result<Self, Self::Error> {
+            if ports.contains(&0) {
+                Err(format!("port 0 of {}", ports.len()))
+            } else {
+                Ok(Ports(ports))
+            }
+        }
+    }
+
+    let email = Email("a@b.c".to_owned());
+    assert_eq!(json::to_string(&email), r#""a@b.c""#);
+    assert_eq!(json::from_str::<Email>(r#""a@b.c""#).unwrap(), email);
+    let err = json::from_str::<Email>(r#""abc""#).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::Invalid);
+    assert_eq!(err.container(), Some("Email"));
+    assert_eq!(
+        err.to_string(),
+        "invalid type or value in `Email`: missing @ at line 1 column 5",
+    );
+    assert!(json::from_str::<Email>("1").is_err());
+
+    let range = Range { first: 3, len: 4 };
+    let j = r#"{"start":3,"end":7}"#;
+    assert_eq!(json::to_string(&range), j);
+    assert_eq!(json::from_str::<Range>(j).unwrap(), range);
+    assert_eq!(
+        json::to_string(&vec![range.clone(), range]),
+        format!("[{},{}]", j, j)
+    );
+
+    assert_eq!(
+        json::from_str::<Ports>("[80, 443]").unwrap(),
+        Ports(vec![80, 443]),
+    );
+    let err = json::from_str::<Ports>("[80, 0]").err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "invalid type or value in `Ports`: port 0 of 2 at line 1 column 7",
+    );
+}
+
+#[test]
+fn test_transparent() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(transparent)]
+    struct Meters {
+        value: f64,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(transparent)]
+    struct Tags<T> {
+        tags: Vec<T>,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Trail {
+        length: Meters,
+        tags: Tags<String>,
+    }
+
+    let meters = Meters { value: 2.5 };
+    assert_eq!(json::to_string(&meters), "2.5");
+    assert_eq!(json::from_str::<Meters>("2.5").unwrap(), meters);
+    assert!(json::from_str::<Meters>(r#"{"value": 2.5}"#).is_err());
+
+    let trail = Trail {
+        length: Meters { value: 1.0 },
+        tags: Tags {
+            tags: vec!["steep".to_owned()],
+        },
+    };
+    let j = r#"{"length":1.0,"tags":["steep"]}"#;
+    assert_eq!(json::to_string(&trail), j);
+    assert_eq!(json::from_str::<Trail>(j).unwrap(), trail);
+}
@@ -0,0 +1,148 @@
+use miniserde::json::{self, StreamDeserializer};
+use miniserde::Deserialize;
+use std::borrow::Cow;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Borrowed<'a> {
+    name: &'a str,
+    tags: Vec<&'a str>,
+    nick: Option<&'a str>,
+    note: Cow<'a, str>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Name<'a>(&'a str);
+
+#[derive(Deserialize, PartialEq, Debug)]
+enum Tag<'a> {
+    Label(&'a str),
+    Pair(&'a str, &'a str),
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Tags<'a> {
+    name: Name<'a>,
+    label: Tag<'a>,
+    pair: Tag<'a>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Outer<'a> {
+    id: u32,
+    #[serde(flatten)]
+    inner: Inner<'a>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Inner<'a> {
+    name: &'a str,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "type")]
+enum Internal<'a> {
+    A { name: &'a str },
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent<'a> {
+    A(&'a str),
+}
+
+// Whether `s` points into `input` rather than into a copy.
+fn within(input: &[u8], s: &str) -> bool {
+    let start = input.as_ptr() as usize;
+    let ptr = s.as_ptr() as usize;
+    start <= ptr && ptr + s.len() <= start + input.len()
+}
+
+#[test]
+fn test_borrowed_str() {
+    let j = r#"{"name": "alice", "tags": ["a", "b"], "nick": "al", "note": "hi"}"#;
+    for out in vec![
+        json::from_str::<Borrowed>(j).unwrap(),
+        json::from_slice::<Borrowed>(j.as_bytes()).unwrap(),
+    ] {
+        assert_eq!(out.name, "alice");
+        assert!(within(j.as_bytes(), out.name));
+        assert_eq!(out.tags, ["a", "b"]);
+        assert!(out.tags.iter().all(|tag| within(j.as_bytes(), tag)));
+        assert!(within(j.as_bytes(), out.nick.unwrap()));
+        match out.note {
+            Cow::Borrowed(note) => assert!(within(j.as_bytes(), note)),
+            Cow::Owned(_) => panic!("expected borrowed note"),
+        }
+    }
+}
+
+#[test]
+fn test_escaped_str() {
+    let j = r#"{"name": "alice", "tags": [], "nick": null, "note": "h\u00ed"}"#;
+    let out = json::from_str::<Borrowed>(j).unwrap();
+    assert_eq!(out.nick, None);
+    match out.note {
+        Cow::Owned(note) => assert_eq!(note, "h\u{ed}"),
+        Cow::Borrowed(_) => panic!("expected owned note"),
+    }
+
+    // A &str has nowhere to hold the unescaped string.
+    let j = r#"{"name": "a\nb", "tags": [], "nick": null, "note": ""}"#;
+    assert!(json::from_str::<Borrowed>(j).is_err());
+    assert!(json::from_str::<&str>(r#""\"""#).is_err());
+    assert_eq!(json::from_str::<Cow<str>>(r#""\"""#).unwrap(), "\"");
+}
+
+#[test]
+fn test_derive() {
+    let j = r#"{"name": "x", "label": {"Label": "y"}, "pair": {"Pair": ["z", "w"]}}"#;
+    let out = json::from_str::<Tags>(j).unwrap();
+    assert_eq!(out.name, Name("x"));
+    assert_eq!(out.label, Tag::Label("y"));
+    assert_eq!(out.pair, Tag::Pair("z", "w"));
+    assert!(within(j.as_bytes(), out.name.0));
+}
+
+#[test]
+fn test_stream() {
+    let j = "\"a\" \"b\\n\" \"c\"";
+    let out = StreamDeserializer::<Cow<str>>::from_str(j)
+        .collect::<miniserde::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(out, ["a", "b\n", "c"]);
+    match (&out[0], &out[1]) {
+        (Cow::Borrowed(_), Cow::Owned(_)) => {}
+        _ => panic!("expected only escaped string to be owned"),
+    }
+}
+
+#[test]
+fn test_from_reader() {
+    // Nothing can be borrowed from a reader, so only owned types are accepted,
+    // and those still go through the same visitors as strings that are copied.
+    let j = r#"["a", "b\n"]"#;
+    let out: Vec<String> = json::from_reader(j.as_bytes()).unwrap();
+    assert_eq!(out, ["a", "b\n"]);
+}
+
+#[test]
+fn test_flatten() {
+    let j = r#"{"id": 1, "name": "x"}"#;
+    let out = json::from_str::<Outer>(j).unwrap();
+    assert_eq!(out.id, 1);
+    assert_eq!(out.inner.name, "x");
+    assert!(within(j.as_bytes(), out.inner.name));
+}
+
+#[test]
+fn test_buffered_before_tag() {
+    let j = r#"{"name": "x", "type": "A"}"#;
+    let Internal::A { name } = json::from_str::<Internal>(j).unwrap();
+    assert_eq!(name, "x");
+    assert!(within(j.as_bytes(), name));
+
+    let j = r#"{"c": "x", "t": "A"}"#;
+    let Adjacent::A(name) = json::from_str::<Adjacent>(j).unwrap();
+    assert_eq!(name, "x");
+    assert!(within(j.as_bytes(), name));
+}
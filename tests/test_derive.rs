@@ -52,3 +52,54 @@ fn test_ser() {
     let expected = r#"{"x":"X","t1":"A","t2":"renamedB","n":{"y":["Y","Y"],"z":null}}"#;
     assert_eq!(actual, expected);
 }
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct UserId(u64);
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Wrapper<T>(T);
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Point(i32, i32, Option<String>);
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Marker;
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Record {
+    id: UserId,
+    tags: Wrapper<Vec<String>>,
+    nested: Wrapper<Nested>,
+    at: Point,
+    marker: Marker,
+}
+
+#[test]
+fn test_unnamed_structs() {
+    let record = Record {
+        id: UserId(7),
+        tags: Wrapper(vec!["a".to_owned()]),
+        nested: Wrapper(Nested {
+            y: None,
+            z: Some("Z".to_owned()),
+        }),
+        at: Point(-1, 2, None),
+        marker: Marker,
+    };
+    let j = r#"{"id":7,"tags":["a"],"nested":{"y":null,"z":"Z"},"at":[-1,2,null],"marker":null}"#;
+    assert_eq!(json::to_string(&record), j);
+    assert_eq!(json::from_str::<Record>(j).unwrap(), record);
+
+    assert_eq!(json::from_str::<UserId>("7").unwrap(), UserId(7));
+    assert_eq!(
+        json::from_str::<Wrapper<String>>(r#""s""#).unwrap(),
+        Wrapper("s".to_owned()),
+    );
+    for j in &["-1", r#""7""#, "[7]", "null"] {
+        assert!(json::from_str::<UserId>(j).is_err(), "{}", j);
+    }
+    for j in &["[1]", "[1,2]", "[1,2,null,3]", r#"{"0":1}"#] {
+        assert!(json::from_str::<Point>(j).is_err(), "{}", j);
+    }
+    assert!(json::from_str::<Marker>("{}").is_err());
+}
@@ -0,0 +1,442 @@
+use miniserde::{json, Deserialize, ErrorKind, Serialize};
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect(u32, u32),
+    #[serde(rename = "polygon")]
+    Polygon {
+        sides: u8,
+        #[serde(rename = "closed", default)]
+        is_closed: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+    Nested(Box<Shape>),
+}
+
+#[test]
+fn test_externally_tagged() {
+    let cases = vec![
+        (Shape::Empty, r#""Empty""#),
+        (Shape::Circle(1.5), r#"{"Circle":1.5}"#),
+        (Shape::Rect(2, 3), r#"{"Rect":[2,3]}"#),
+        (
+            Shape::Polygon {
+                sides: 5,
+                is_closed: true,
+                label: None,
+            },
+            r#"{"polygon":{"sides":5,"closed":true}}"#,
+        ),
+        (
+            Shape::Polygon {
+                sides: 3,
+                is_closed: false,
+                label: Some("tri".to_owned()),
+            },
+            r#"{"polygon":{"sides":3,"closed":false,"label":"tri"}}"#,
+        ),
+        (
+            Shape::Nested(Box::new(Shape::Rect(1, 1))),
+            r#"{"Nested":{"Rect":[1,1]}}"#,
+        ),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Shape>(j).unwrap(), value);
+    }
+
+    let j = r#"{"polygon": {"sides": 4}}"#;
+    let expected = Shape::Polygon {
+        sides: 4,
+        is_closed: false,
+        label: None,
+    };
+    assert_eq!(json::from_str::<Shape>(j).unwrap(), expected);
+}
+
+#[test]
+fn test_externally_tagged_errors() {
+    for j in &[
+        r#"{}"#,
+        r#""Circle""#,
+        r#"{"Empty": null}"#,
+        r#"{"Unknown": 1}"#,
+        r#"{"Circle": 1, "Rect": [1, 2]}"#,
+        r#"{"Rect": [1]}"#,
+        r#"{"Rect": [1, 2, 3]}"#,
+        r#"{"Rect": {"0": 1, "1": 2}}"#,
+    ] {
+        assert!(json::from_str::<Shape>(j).is_err(), "{}", j);
+    }
+
+    let j = r#"{"polygon": {"closed": true}}"#;
+    let err = json::from_str::<Shape>(j).err().unwrap();
+    assert_eq!(err.field(), Some("sides"));
+    assert_eq!(err.container(), Some("Shape::Polygon"));
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event {
+    Click { x: i32, y: i32 },
+    KeyPress { key: String, repeat: Option<u32> },
+    Focus,
+}
+
+#[test]
+fn test_internally_tagged() {
+    let cases = vec![
+        (
+            Event::Click { x: 1, y: -2 },
+            r#"{"type":"click","x":1,"y":-2}"#,
+        ),
+        (
+            Event::KeyPress {
+                key: "a".to_owned(),
+                repeat: None,
+            },
+            r#"{"type":"key_press","key":"a","repeat":null}"#,
+        ),
+        (Event::Focus, r#"{"type":"focus"}"#),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Event>(j).unwrap(), value);
+    }
+
+    // The tag does not need to come first.
+    let j = r#"{"x": 1, "extra": [{"nested": true}], "y": 2, "type": "click"}"#;
+    assert_eq!(
+        json::from_str::<Event>(j).unwrap(),
+        Event::Click { x: 1, y: 2 },
+    );
+    let j = r#"{"repeat": 3, "type": "key_press", "key": "b"}"#;
+    let expected = Event::KeyPress {
+        key: "b".to_owned(),
+        repeat: Some(3),
+    };
+    assert_eq!(json::from_str::<Event>(j).unwrap(), expected);
+    let j = r#"{"ignored": 1, "type": "focus", "also": 2}"#;
+    assert_eq!(json::from_str::<Event>(j).unwrap(), Event::Focus);
+}
+
+#[test]
+fn test_internally_tagged_errors() {
+    for j in &[
+        r#""focus""#,
+        r#"{"type": "unknown"}"#,
+        r#"{"type": 1, "x": 1, "y": 2}"#,
+        r#"{"type": "click", "x": 1}"#,
+        r#"{"x": "1", "y": 2, "type": "click"}"#,
+        r#"{"type": "click", "type": "click", "x": 1, "y": 2}"#,
+    ] {
+        assert!(json::from_str::<Event>(j).is_err(), "{}", j);
+    }
+
+    let j = r#"{"x": 1, "y": 2}"#;
+    let err = json::from_str::<Event>(j).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.field(), Some("type"));
+    assert_eq!(err.container(), Some("Event"));
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+enum Message {
+    Ping,
+    Text(String),
+    Move(i32, i32),
+    Resize { width: u32, height: u32 },
+}
+
+#[test]
+fn test_adjacently_tagged() {
+    let cases = vec![
+        (Message::Ping, r#"{"t":"Ping"}"#),
+        (Message::Text("hi".to_owned()), r#"{"t":"Text","c":"hi"}"#),
+        (Message::Move(-1, 2), r#"{"t":"Move","c":[-1,2]}"#),
+        (
+            Message::Resize {
+                width: 3,
+                height: 4,
+            },
+            r#"{"t":"Resize","c":{"width":3,"height":4}}"#,
+        ),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Message>(j).unwrap(), value);
+    }
+
+    // The content may come before the tag, and a unit variant may have null
+    // content.
+    let j = r#"{"c": {"height": 2, "width": 1}, "other": 0, "t": "Resize"}"#;
+    let expected = Message::Resize {
+        width: 1,
+        height: 2,
+    };
+    assert_eq!(json::from_str::<Message>(j).unwrap(), expected);
+    let j = r#"{"c": "later", "t": "Text"}"#;
+    let expected = Message::Text("later".to_owned());
+    assert_eq!(json::from_str::<Message>(j).unwrap(), expected);
+    let j = r#"{"t": "Ping", "c": null}"#;
+    assert_eq!(json::from_str::<Message>(j).unwrap(), Message::Ping);
+}
+
+#[test]
+fn test_adjacently_tagged_errors() {
+    for j in &[
+        r#""Ping""#,
+        r#"{"t": "Unknown"}"#,
+        r#"{"t": "Ping", "c": 1}"#,
+        r#"{"t": "Move", "c": [1]}"#,
+        r#"{"c": "x", "t": "Move"}"#,
+        r#"{"t": "Text", "c": "a", "c": "b"}"#,
+    ] {
+        assert!(json::from_str::<Message>(j).is_err(), "{}", j);
+    }
+
+    let err = json::from_str::<Message>(r#"{"c": "hi"}"#).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.field(), Some("t"));
+    assert_eq!(err.container(), Some("Message"));
+
+    let err = json::from_str::<Message>(r#"{"t": "Text"}"#).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.field(), Some("c"));
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Setting {
+    Off,
+    Flag(bool),
+    Level(u8),
+    Range(u8, u8),
+    Named { name: String },
+    Other(Vec<String>),
+}
+
+#[test]
+fn test_untagged() {
+    let cases = vec![
+        (Setting::Off, "null"),
+        (Setting::Flag(true), "true"),
+        (Setting::Level(7), "7"),
+        (Setting::Range(1, 2), "[1,2]"),
+        (
+            Setting::Named {
+                name: "x".to_owned(),
+            },
+            r#"{"name":"x"}"#,
+        ),
+        (
+            Setting::Other(vec!["a".to_owned(), "b".to_owned()]),
+            r#"["a","b"]"#,
+        ),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Setting>(j).unwrap(), value);
+    }
+
+    // Variants are tried in order, so the first one that fits wins.
+    assert_eq!(
+        json::from_str::<Setting>("[]").unwrap(),
+        Setting::Other(Vec::new())
+    );
+    assert!(json::from_str::<Setting>("300").is_err());
+    assert!(json::from_str::<Setting>(r#"{"other":1}"#).is_err());
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+enum Response<T, E>
+where
+    E: Clone,
+{
+    Ok(T),
+    Err {
+        error: E,
+        retry: Option<Box<Response<T, E>>>,
+    },
+    Pair(T, E),
+    Pending,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum Page<T> {
+    Items { items: Vec<T> },
+    End,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+#[test]
+fn test_generic() {
+    let cases = vec![
+        (Response::Ok(1), r#"{"Ok":1}"#),
+        (
+            Response::Err {
+                error: "e".to_owned(),
+                retry: Some(Box::new(Response::Pending)),
+            },
+            r#"{"Err":{"error":"e","retry":"Pending"}}"#,
+        ),
+        (Response::Pair(2, "p".to_owned()), r#"{"Pair":[2,"p"]}"#),
+        (Response::Pending, r#""Pending""#),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Response<u8, String>>(j).unwrap(), value);
+    }
+
+    let page = Page::Items {
+        items: vec![true, false],
+    };
+    let j = r#"{"kind":"Items","items":[true,false]}"#;
+    assert_eq!(json::to_string(&page), j);
+    assert_eq!(json::from_str::<Page<bool>>(j).unwrap(), page);
+
+    let j = "[1,2]";
+    assert_eq!(json::to_string(&OneOrMany::Many(vec![1, 2])), j);
+    assert_eq!(
+        json::from_str::<OneOrMany<u8>>(j).unwrap(),
+        OneOrMany::Many(vec![1, 2]),
+    );
+    assert_eq!(
+        json::from_str::<OneOrMany<u8>>("3").unwrap(),
+        OneOrMany::One(3)
+    );
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(repr = "int")]
+#[repr(i16)]
+enum Level {
+    Low = -1,
+    Normal,
+    High = 10,
+    Max = i16::max_value(),
+}
+
+#[test]
+fn test_int_repr() {
+    let cases = vec![
+        (Level::Low, "-1"),
+        (Level::Normal, "0"),
+        (Level::High, "10"),
+        (Level::Max, "32767"),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Level>(j).unwrap(), value);
+    }
+
+    for j in &["1", "-2", "32768", "1.0", r#""Normal""#, "null"] {
+        assert!(json::from_str::<Level>(j).is_err(), "{}", j);
+    }
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+enum Status {
+    Active,
+    Point(i32, i32),
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Color {
+    Red,
+    Custom {
+        rgb: u32,
+    },
+    #[serde(other)]
+    Other(String),
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum Kind {
+    Known {
+        id: u8,
+    },
+    #[serde(other)]
+    Unknown(String),
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+enum Op {
+    Add(i32),
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+#[serde(repr = "int")]
+enum Code {
+    Ok = 200,
+    NotFound = 404,
+    #[serde(other)]
+    Unknown = 0,
+}
+
+#[test]
+fn test_other() {
+    let j = r#""Retired""#;
+    assert_eq!(json::from_str::<Status>(j).unwrap(), Status::Unknown);
+    let j = r#"{"Moved": {"to": [1, 2]}}"#;
+    assert_eq!(json::from_str::<Status>(j).unwrap(), Status::Unknown);
+    let j = r#"{"Point": [1, 2]}"#;
+    assert_eq!(json::from_str::<Status>(j).unwrap(), Status::Point(1, 2));
+    assert_eq!(json::to_string(&Status::Unknown), r#""Unknown""#);
+    // A known unit variant is not mistaken for an unknown one with data.
+    for j in &[r#"{"Active": null}"#, r#"{"Unknown": null}"#] {
+        assert!(json::from_str::<Status>(j).is_err(), "{}", j);
+    }
+
+    let value = Color::Other("teal".to_owned());
+    assert_eq!(json::to_string(&value), r#""teal""#);
+    assert_eq!(json::from_str::<Color>(r#""teal""#).unwrap(), value);
+    assert_eq!(json::from_str::<Color>(r#""red""#).unwrap(), Color::Red);
+    let j = r#"{"gradient": [1, 2]}"#;
+    let expected = Color::Other("gradient".to_owned());
+    assert_eq!(json::from_str::<Color>(j).unwrap(), expected);
+
+    let value = Kind::Unknown("future".to_owned());
+    let j = r#"{"type":"future"}"#;
+    assert_eq!(json::to_string(&value), j);
+    assert_eq!(json::from_str::<Kind>(j).unwrap(), value);
+    let j = r#"{"id": 1, "type": "future", "extra": [true]}"#;
+    assert_eq!(json::from_str::<Kind>(j).unwrap(), value);
+    let j = r#"{"type": "Known", "id": 1}"#;
+    assert_eq!(json::from_str::<Kind>(j).unwrap(), Kind::Known { id: 1 });
+
+    for j in &[
+        r#"{"t": "Mul"}"#,
+        r#"{"t": "Mul", "c": [1, 2]}"#,
+        r#"{"c": {"x": 1}, "t": "Mul"}"#,
+    ] {
+        assert_eq!(json::from_str::<Op>(j).unwrap(), Op::Unsupported, "{}", j);
+    }
+    assert_eq!(
+        json::from_str::<Op>(r#"{"t": "Add", "c": 1}"#).unwrap(),
+        Op::Add(1)
+    );
+
+    assert_eq!(json::from_str::<Code>("404").unwrap(), Code::NotFound);
+    assert_eq!(json::from_str::<Code>("500").unwrap(), Code::Unknown);
+    assert_eq!(json::from_str::<Code>("-1").unwrap(), Code::Unknown);
+    assert_eq!(json::to_string(&Code::Unknown), "0");
+}
@@ -0,0 +1,139 @@
+use miniserde::{json, Deserialize, ErrorKind};
+
+#[derive(Deserialize, Debug)]
+struct Config {
+    name: String,
+    port: u16,
+}
+
+#[test]
+fn test_ok() {
+    let config: Config = json::from_str(r#"{"name": "x", "port": 80}"#).unwrap();
+    assert_eq!(config.name, "x");
+    assert_eq!(config.port, 80);
+}
+
+#[test]
+fn test_syntax() {
+    let j = "{\n  \"name\": \"x\",\n  \"port\": 80,,\n}";
+    let err = json::from_str::<Config>(j).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    assert_eq!(err.line(), Some(3));
+    assert_eq!(err.column(), Some(13));
+    assert_eq!(err.offset(), Some(30));
+    assert_eq!(err.to_string(), "invalid JSON syntax at line 3 column 13");
+
+    // A mismatched bracket at the very end is not mistaken for an early end.
+    let err = json::from_str::<json::Value>("[1, 2}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    assert_eq!(err.offset(), Some(5));
+}
+
+#[test]
+fn test_eof() {
+    for j in &["", "[1, 2", "{\"name\": \"x", "tru", "1e"] {
+        let err = json::from_str::<json::Value>(j).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Eof, "{}", j);
+        assert_eq!(err.offset(), Some(j.len()));
+    }
+
+    // Complete but malformed input is not mistaken for an early end, even if
+    // the offending byte is the last one.
+    for j in &["[1,]", "{\"a\": 1}}", "\"\\q\""] {
+        let err = json::from_str::<json::Value>(j).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Syntax, "{}", j);
+    }
+}
+
+#[test]
+fn test_invalid() {
+    let j = r#"{"name": "x", "port": 70000}"#;
+    let err = json::from_str::<Config>(j).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Invalid);
+    assert_eq!(err.line(), Some(1));
+    assert_eq!(err.column(), Some(27));
+}
+
+#[test]
+fn test_missing_field() {
+    let j = r#"{"name": "x"}"#;
+    let err = json::from_str::<Config>(j).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.field(), Some("port"));
+    assert_eq!(err.container(), Some("Config"));
+    assert_eq!(err.offset(), Some(j.len()));
+    assert_eq!(
+        err.to_string(),
+        "missing field `port` in `Config` at line 1 column 13",
+    );
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Pair(String, u16);
+
+    let err = json::from_str::<Pair>(r#"["x"]"#).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.field(), Some("1"));
+    assert_eq!(err.container(), Some("Pair"));
+    assert_eq!(
+        err.to_string(),
+        "missing field `1` in `Pair` at line 1 column 5",
+    );
+}
+
+#[test]
+fn test_unit_error() {
+    let err = miniserde::Error;
+    assert_eq!(err.kind(), ErrorKind::Invalid);
+    assert_eq!(err.offset(), None);
+    assert_eq!(err.to_string(), "invalid type or value");
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Users {
+    users: Vec<User>,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct User {
+    address: Address,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Address {
+    zip: u32,
+}
+
+#[test]
+fn test_path() {
+    let j = r#"{"users": [
+        {"address": {"zip": 1}},
+        {"address": {"zip": 2}},
+        {"address": {"zip": 3}},
+        {"address": {"zip": "x"}}
+    ]}"#;
+    let err = json::from_str::<Users>(j).unwrap_err();
+    assert_eq!(err.path(), Some(".users[3].address.zip"));
+    assert_eq!(
+        err.to_string(),
+        ".users[3].address.zip: invalid type or value at line 5 column 31",
+    );
+
+    let j = r#"{"users": [{"address": {}}]}"#;
+    let err = json::from_str::<Users>(j).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.path(), Some(".users[0].address"));
+
+    let j = r#"{"users": [{"address": {"zip": -1}}]}"#;
+    let err = json::from_str::<Users>(j).unwrap_err();
+    assert_eq!(err.path(), Some(".users[0].address.zip"));
+
+    let err = json::from_str::<(u8, u8)>("[1, 2, 3]").unwrap_err();
+    assert_eq!(err.path(), Some("[2]"));
+
+    let err = json::from_str::<Users>("[]").unwrap_err();
+    assert_eq!(err.path(), Some("."));
+}
@@ -0,0 +1,116 @@
+use miniserde::de::DeserializeOwned;
+use miniserde::json::{Limits, Value};
+use miniserde::{Deserialize, ErrorKind};
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Record {
+    name: String,
+    tags: Vec<String>,
+}
+
+fn check<T: DeserializeOwned>(limits: Limits, j: &str) -> miniserde::Result<T> {
+    let from_str = limits.from_str::<T>(j);
+    let from_slice = limits.from_slice::<T>(j.as_bytes());
+    let from_reader = limits.from_reader::<_, T>(j.as_bytes());
+    for other in &[&from_slice, &from_reader] {
+        match (&from_str, other) {
+            (Ok(_), Ok(_)) => {}
+            (Err(expected), Err(err)) => {
+                assert_eq!(err.kind(), expected.kind(), "{}", j);
+                assert_eq!(err.offset(), expected.offset(), "{}", j);
+                assert_eq!(err.path(), expected.path(), "{}", j);
+            }
+            _ => panic!("inconsistent result for {}", j),
+        }
+    }
+    from_str
+}
+
+#[test]
+fn test_unlimited() {
+    let j = r#"{"name": "x", "tags": ["a", "b"]}"#;
+    check::<Record>(Limits::new(), j).unwrap();
+    check::<Record>(Limits::default(), j).unwrap();
+}
+
+#[test]
+fn test_max_depth() {
+    let limits = Limits::new().max_depth(2);
+    check::<Value>(limits, "[[1], {\"a\": 1}]").unwrap();
+    check::<Value>(Limits::new().max_depth(0), "1").unwrap();
+
+    let err = check::<Value>(limits, "[[1], {\"a\": []}]").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TooDeep);
+    assert_eq!(err.offset(), Some(12));
+    assert_eq!(err.path(), Some("[1].a"));
+
+    let hostile = "[".repeat(100_000);
+    let err = check::<Value>(limits, &hostile).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TooDeep);
+    assert_eq!(err.offset(), Some(2));
+}
+
+#[test]
+fn test_max_input_len() {
+    let j = r#"{"name": "x", "tags": []}"#;
+    let limits = Limits::new().max_input_len(j.len());
+    check::<Record>(limits, j).unwrap();
+
+    let err = check::<Record>(limits, &format!("{} ", j)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InputTooLong);
+    assert_eq!(err.offset(), Some(j.len()));
+
+    let err = check::<Value>(limits, &format!("[{}]", j)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InputTooLong);
+
+    let err = check::<Value>(limits, &"1".repeat(100_000)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InputTooLong);
+}
+
+#[test]
+fn test_max_str_len() {
+    let limits = Limits::new().max_str_len(4);
+    check::<Record>(limits, r#"{"name": "éxy", "tags": ["abcd"]}"#).unwrap();
+
+    let err = check::<Record>(limits, r#"{"name": "x", "tags": ["abcde"]}"#).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::StringTooLong);
+    assert_eq!(err.path(), Some(".tags[0]"));
+
+    let err = check::<Value>(limits, r#"{"longer": 1}"#).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::StringTooLong);
+
+    // Input from a reader is rejected without buffering the whole string, so
+    // before reaching the closing quote.
+    let long = format!("\"{}\"", "x".repeat(100_000));
+    let err = limits.from_str::<Value>(&long).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::StringTooLong);
+    assert_eq!(err.offset(), Some(long.len() - 1));
+    let err = limits.from_reader::<_, Value>(long.as_bytes()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::StringTooLong);
+    assert!(err.offset().unwrap() < long.len() - 1);
+}
+
+#[test]
+fn test_max_elements() {
+    let limits = Limits::new().max_elements(2);
+    check::<Value>(limits, r#"[[1, 2], {"a": 1, "b": [3]}]"#).unwrap();
+
+    let err = check::<Value>(limits, "[1, [2, 3, 4]]").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TooManyElements);
+    assert_eq!(err.path(), Some("[1][2]"));
+
+    let err = check::<Value>(limits, r#"{"a": 1, "b": 2, "c": 3}"#).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TooManyElements);
+    assert_eq!(err.path(), Some(".c"));
+}
+
+#[test]
+fn test_max_values() {
+    let j = r#"{"name": "x", "tags": ["a", "b"]}"#;
+    check::<Record>(Limits::new().max_values(5), j).unwrap();
+
+    let err = check::<Record>(Limits::new().max_values(4), j).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TooManyValues);
+    assert_eq!(err.path(), Some(".tags[1]"));
+}
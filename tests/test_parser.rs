@@ -0,0 +1,193 @@
+use miniserde::json::{self, Limits, Number, Parser, Token, Value};
+use miniserde::ErrorKind;
+
+// Render each token compactly along with its offset.
+fn tokens(mut parser: Parser) -> miniserde::Result<Vec<String>> {
+    let mut out = Vec::new();
+    while let Some(token) = parser.next() {
+        let (offset, token) = token?;
+        let token = match token {
+            Token::Null => "null".to_owned(),
+            Token::Bool(b) => b.to_string(),
+            Token::Number(Number::U64(n)) => format!("u{}", n),
+            Token::Number(Number::I64(n)) => format!("i{}", n),
+            Token::Number(Number::F64(n)) => format!("f{}", n),
+            Token::Str(s) => format!("{:?}", s),
+            Token::SeqStart => "[".to_owned(),
+            Token::SeqEnd => "]".to_owned(),
+            Token::MapStart => "{".to_owned(),
+            Token::MapEnd => "}".to_owned(),
+            Token::Key(k) => format!("{:?}:", k),
+        };
+        out.push(format!("{}@{}", token, offset));
+    }
+    Ok(out)
+}
+
+#[test]
+fn test_tokens() {
+    let j = r#" {"a": [1, -2, 0.5, "x\ny"], "b": {"c": null}, "d": true, "e": []} "#;
+    let expected = [
+        "{@1",
+        "\"a\":@2",
+        "[@7",
+        "u1@8",
+        "i-2@11",
+        "f0.5@15",
+        "\"x\\ny\"@20",
+        "]@26",
+        "\"b\":@29",
+        "{@34",
+        "\"c\":@35",
+        "null@40",
+        "}@44",
+        "\"d\":@47",
+        "true@52",
+        "\"e\":@58",
+        "[@63",
+        "]@64",
+        "}@65",
+    ];
+    assert_eq!(tokens(Parser::from_str(j)).unwrap(), expected);
+    assert_eq!(tokens(Parser::from_slice(j.as_bytes())).unwrap(), expected);
+    assert_eq!(tokens(Parser::from_reader(j.as_bytes())).unwrap(), expected);
+
+    assert_eq!(
+        tokens(Parser::from_str("\"\\u00e9\"")).unwrap(),
+        ["\"\u{e9}\"@0"]
+    );
+    assert_eq!(tokens(Parser::from_str(" false ")).unwrap(), ["false@1"]);
+}
+
+#[test]
+fn test_errors_match_from_str() {
+    let inputs = [
+        "",
+        "[",
+        "{1}",
+        "[1,]",
+        "[,1]",
+        "[1 2]",
+        "[1]]",
+        "1 2",
+        "{\"a\" 1}",
+        "{\"a\": 1,}",
+        "{,}",
+        "{\"a\": [1, {\"b\": x}]}",
+        "{\"a\": {\"b\": 1} \"c\": 2}",
+        "{\"a\\q\": 1}",
+        "[1, 2}",
+        "\"\\ud800\"",
+    ];
+    for j in &inputs {
+        let expected = json::from_str::<Value>(j).unwrap_err();
+        for parser in vec![
+            Parser::from_str(j),
+            Parser::from_slice(j.as_bytes()),
+            Parser::from_reader(j.as_bytes()),
+        ] {
+            let err = tokens(parser).unwrap_err();
+            assert_eq!(err.kind(), expected.kind(), "{}", j);
+            assert_eq!(err.offset(), expected.offset(), "{}", j);
+            assert_eq!(err.path(), expected.path(), "{}", j);
+        }
+    }
+}
+
+#[test]
+fn test_done_after_error() {
+    let mut parser = Parser::from_str("[1, x, 2]");
+    assert!(parser.next().unwrap().is_ok());
+    assert!(parser.next().unwrap().is_ok());
+    let err = parser.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    assert_eq!(err.path(), Some("[1]"));
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn test_skip_subtree() {
+    // Collect the strings of a document, other than those inside of any value
+    // whose key is "secret".
+    let j = r#"{"a": "x", "secret": {"b": ["y"]}, "c": [{"secret": "z"}, "w"]}"#;
+    let mut parser = Parser::from_str(j);
+    let mut strings = Vec::new();
+    let mut skip = false;
+    while let Some(token) = parser.next() {
+        match token.unwrap().1 {
+            Token::Key("secret") => skip = true,
+            Token::Str(s) if !skip => strings.push(s.to_owned()),
+            Token::SeqStart | Token::MapStart if skip => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match parser.next().unwrap().unwrap().1 {
+                        Token::SeqStart | Token::MapStart => depth += 1,
+                        Token::SeqEnd | Token::MapEnd => depth -= 1,
+                        _ => {}
+                    }
+                }
+                skip = false;
+            }
+            _ => skip = false,
+        }
+    }
+    assert_eq!(strings, ["x", "w"]);
+}
+
+#[test]
+fn test_limits() {
+    let cases = [
+        (Limits::new().max_depth(2), r#"[[1], {"a": [[2]]}]"#),
+        (Limits::new().max_elements(2), r#"[1, 2, 3]"#),
+        (
+            Limits::new().max_elements(2),
+            r#"{"a": [1, 2], "b": 2, "c": 3}"#,
+        ),
+        (Limits::new().max_values(3), r#"[1, [2]]"#),
+        (Limits::new().max_str_len(2), r#"["ab", "abc"]"#),
+        (Limits::new().max_input_len(4), r#"[1, 2]"#),
+    ];
+    for (limits, j) in &cases {
+        let expected = limits.from_str::<Value>(j).unwrap_err();
+        for parser in vec![
+            limits.parser_from_str(j),
+            limits.parser_from_slice(j.as_bytes()),
+            limits.parser_from_reader(j.as_bytes()),
+        ] {
+            let err = tokens(parser).unwrap_err();
+            assert_eq!(err.kind(), expected.kind(), "{}", j);
+            assert_eq!(err.path(), expected.path(), "{}", j);
+            assert_eq!(err.offset(), expected.offset(), "{}", j);
+        }
+    }
+
+    let j = r#"[[1], {"a": [2]}]"#;
+    assert!(tokens(Limits::new().max_depth(3).parser_from_str(j)).is_ok());
+    assert!(tokens(Limits::new().max_elements(2).parser_from_str(j)).is_ok());
+}
+
+#[test]
+fn test_borrowed() {
+    // Strings without escapes are lent straight out of the input.
+    let j = r#"{"key": ["value", "esc\u00e9"]}"#;
+    let mut parser = Parser::from_str(j);
+    let mut strings = Vec::new();
+    while let Some(token) = parser.next() {
+        match token.unwrap().1 {
+            Token::Key(s) | Token::Str(s) => {
+                let start = j.as_ptr() as usize;
+                let ptr = s.as_ptr() as usize;
+                strings.push((s.to_owned(), start <= ptr && ptr < start + j.len()));
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(
+        strings,
+        [
+            ("key".to_owned(), true),
+            ("value".to_owned(), true),
+            ("esc\u{e9}".to_owned(), false),
+        ],
+    );
+}
@@ -0,0 +1,166 @@
+use miniserde::json::{self, Value};
+use miniserde::{Deserialize, ErrorKind};
+use std::io::{self, Read};
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Record {
+    name: String,
+    tags: Vec<String>,
+    score: f64,
+}
+
+// Hands out the input one byte at a time, so that every token crosses a
+// boundary between reads.
+struct Trickle<'a> {
+    input: &'a [u8],
+    interrupt: bool,
+}
+
+impl<'a> Read for Trickle<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        match self.input.split_first() {
+            Some((&first, rest)) if !buf.is_empty() => {
+                buf[0] = first;
+                self.input = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+fn trickle(input: &str) -> Trickle<'_> {
+    Trickle {
+        input: input.as_bytes(),
+        interrupt: false,
+    }
+}
+
+struct Broken;
+
+// Fails the test if read from, like a reader that would block.
+struct Unreadable;
+
+impl Read for Unreadable {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        panic!("read past the error");
+    }
+}
+
+impl Read for Broken {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "broken"))
+    }
+}
+
+#[test]
+fn test_from_slice() {
+    let j = br#"{"name": "x\u00e9\n", "tags": ["\ud83d\ude00", "y"], "score": -1.5e1}"#;
+    let expected = Record {
+        name: "x\u{e9}\n".to_owned(),
+        tags: vec!["\u{1f600}".to_owned(), "y".to_owned()],
+        score: -15.0,
+    };
+    assert_eq!(json::from_slice::<Record>(j).unwrap(), expected);
+
+    let j = "{\"name\": \"caf\u{e9} \u{2603}\", \"tags\": [\"\u{1f600}\"], \"score\": 0}";
+    let record = json::from_slice::<Record>(j.as_bytes()).unwrap();
+    assert_eq!(record.name, "caf\u{e9} \u{2603}");
+    assert_eq!(record.tags, ["\u{1f600}"]);
+}
+
+#[test]
+fn test_from_slice_invalid_utf8() {
+    let err = json::from_slice::<String>(b"\"ab\xffc\"").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    assert_eq!(err.offset(), Some(5));
+
+    let err = json::from_slice::<Value>(b"[1, \xe2\x98]").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    assert_eq!(err.offset(), Some(5));
+}
+
+#[test]
+fn test_from_reader() {
+    let j = r#"{"name": "a \"quoted\" name", "tags": ["one", "two"], "score": 12.25}"#;
+    let expected = Record {
+        name: "a \"quoted\" name".to_owned(),
+        tags: vec!["one".to_owned(), "two".to_owned()],
+        score: 12.25,
+    };
+    assert_eq!(
+        json::from_reader::<_, Record>(j.as_bytes()).unwrap(),
+        expected
+    );
+    assert_eq!(
+        json::from_reader::<_, Record>(trickle(j)).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_from_reader_large() {
+    let long = "x\u{e9}".repeat(10_000);
+    let tags: Vec<String> = (0..2_000).map(|i| i.to_string()).collect();
+    let j = format!(
+        r#"{{"name": "{}", "tags": {}, "score": 1}}"#,
+        long,
+        json::to_string(&tags),
+    );
+    let record: Record = json::from_reader(j.as_bytes()).unwrap();
+    assert_eq!(record.name, long);
+    assert_eq!(record.tags, tags);
+}
+
+#[test]
+fn test_from_reader_errors() {
+    let inputs = &[
+        "{\n  \"name\": \"x\",\n  \"tags\": [],,\n}",
+        r#"{"name": "x", "tags": [1], "score": 0}"#,
+        r#"{"name": "x", "tags": []}"#,
+        "[1, 2",
+        "",
+        "{\"name\": \"x",
+        "1 2",
+        "[1,]",
+    ];
+    for j in inputs {
+        let expected = json::from_str::<Record>(j).unwrap_err();
+        for err in vec![
+            json::from_reader::<_, Record>(j.as_bytes()).unwrap_err(),
+            json::from_reader::<_, Record>(trickle(j)).unwrap_err(),
+        ] {
+            assert_eq!(err.kind(), expected.kind(), "{}", j);
+            assert_eq!(err.offset(), expected.offset(), "{}", j);
+            assert_eq!(err.line(), expected.line(), "{}", j);
+            assert_eq!(err.column(), expected.column(), "{}", j);
+            assert_eq!(err.path(), expected.path(), "{}", j);
+        }
+    }
+}
+
+#[test]
+fn test_from_reader_io_error() {
+    let err = json::from_reader::<_, Value>(Broken).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io);
+    assert_eq!(err.to_string(), "failed to read input at line 1 column 0");
+
+    let reader = b"[1, 2".chain(Broken);
+    let err = json::from_reader::<_, Value>(reader).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io);
+    assert_eq!(err.offset(), Some(5));
+}
+
+#[test]
+fn test_error_without_reading() {
+    // The error is found in the input read so far, and reporting it does not
+    // go looking for more input.
+    let reader = b"[1,]".chain(Unreadable);
+    let err = json::from_reader::<_, Value>(reader).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    assert_eq!(err.offset(), Some(4));
+}
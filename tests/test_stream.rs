@@ -0,0 +1,242 @@
+use miniserde::json::{self, Limits, StreamDeserializer, Value};
+use miniserde::{Deserialize, ErrorKind};
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Record {
+    id: u32,
+    name: String,
+}
+
+fn record(id: u32, name: &str) -> Record {
+    Record {
+        id,
+        name: name.to_owned(),
+    }
+}
+
+#[test]
+fn test_json_lines() {
+    let j = "{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2, \"name\": \"b\"}\n\n{\"id\": 3, \"name\": \"c\"}\n";
+    let records: Vec<Record> = StreamDeserializer::from_str(j)
+        .collect::<miniserde::Result<_>>()
+        .unwrap();
+    assert_eq!(records, [record(1, "a"), record(2, "b"), record(3, "c")]);
+
+    let from_slice = StreamDeserializer::<Record>::from_slice(j.as_bytes());
+    assert_eq!(from_slice.map(Result::unwrap).count(), 3);
+
+    let from_reader = StreamDeserializer::<Record>::from_reader(j.as_bytes());
+    assert_eq!(from_reader.map(Result::unwrap).count(), 3);
+}
+
+#[test]
+fn test_offsets() {
+    let j = r#"  1 "two" [3]{"four": 4}  null"#;
+    let mut stream = StreamDeserializer::<Value>::from_str(j);
+    let mut offsets = Vec::new();
+    while let Some(value) = stream.next() {
+        value.unwrap();
+        offsets.push(stream.offset());
+    }
+    assert_eq!(offsets, [2, 4, 10, 13, 26]);
+
+    assert!(StreamDeserializer::<Value>::from_str("").next().is_none());
+    assert!(StreamDeserializer::<Value>::from_str(" \n ")
+        .next()
+        .is_none());
+}
+
+#[test]
+fn test_separation() {
+    let mut stream = StreamDeserializer::<Value>::from_str("12[3]true\"x\"null1");
+    for _ in 0..4 {
+        assert!(stream.next().unwrap().is_ok());
+    }
+    let err = stream.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    assert_eq!(err.offset(), Some(16));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn test_resume() {
+    let j = "{\"id\": 1, \"name\": \"a\"}\n\
+             {\"id\": 2, \"name\": }\n\
+             {\"id\": \"3\", \"name\": \"c\"}\n\
+             {\"id\": 4}\n\
+             {\"id\": 5, \"name\": \"e\"}\n\
+             {\"id\": 6,";
+
+    for mut stream in vec![
+        StreamDeserializer::<Record>::from_str(j),
+        StreamDeserializer::<Record>::from_reader(j.as_bytes()),
+    ] {
+        assert_eq!(stream.next().unwrap().unwrap(), record(1, "a"));
+
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Syntax);
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(stream.offset(), 23);
+
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Invalid);
+        assert_eq!(err.path(), Some(".id"));
+        assert_eq!(err.line(), Some(3));
+
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingField);
+        assert_eq!(err.field(), Some("name"));
+
+        assert_eq!(stream.next().unwrap().unwrap(), record(5, "e"));
+
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Eof);
+        assert_eq!(err.offset(), Some(j.len()));
+
+        assert!(stream.next().is_none());
+    }
+
+    // A record cut short is only found to be malformed on the next line, which
+    // is then read as a record of its own.
+    let j = "{\"id\": 1\n{\"id\": 2}\n{\"id\": 3}\n";
+    for mut stream in vec![
+        StreamDeserializer::<Value>::from_str(j),
+        StreamDeserializer::<Value>::from_reader(j.as_bytes()),
+    ] {
+        let err = stream.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Syntax);
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(
+            json::to_string(&stream.next().unwrap().unwrap()),
+            "{\"id\":2}"
+        );
+        assert_eq!(
+            json::to_string(&stream.next().unwrap().unwrap()),
+            "{\"id\":3}"
+        );
+        assert!(stream.next().is_none());
+    }
+}
+
+#[test]
+fn test_matches_from_str() {
+    let values = ["null", "[1, {\"a\": [true]}]", "-0.5", "\"\\u00e9\""];
+    let j = values.join("\n");
+    let streamed: Vec<Value> = StreamDeserializer::from_str(&j)
+        .map(Result::unwrap)
+        .collect();
+    for (value, j) in streamed.iter().zip(&values) {
+        assert_eq!(
+            json::to_string(value),
+            json::to_string(&json::from_str::<Value>(j).unwrap())
+        );
+    }
+}
+
+#[test]
+fn test_resume_multiline() {
+    let j = "{\"name\":\n  2, \"id\": 1}\n{\"id\": 3, \"name\": \"c\"}\n";
+    let mut stream = StreamDeserializer::<Record>::from_str(j);
+    let err = stream.next().unwrap().unwrap_err();
+    assert_eq!(err.path(), Some(".name"));
+    assert_eq!(err.line(), Some(2));
+    // The rest of the record is on the line where the error was detected, so
+    // it is read again from the start of that line, and then skipped.
+    let err = stream.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Invalid);
+    assert_eq!(err.line(), Some(2));
+    assert_eq!(stream.next().unwrap().unwrap(), record(3, "c"));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn test_resume_across_refill() {
+    // The line that is gone back to after the error begins 2 bytes before the
+    // end of the first 8 KiB that are read from the reader.
+    let j = format!(
+        "{{\"id\": 1, \"name\": \"{}\"}}\n{{\"name\":\n  2, \"id\": 2}}\n{{\"id\": 3, \"name\": \"c\"}}\n",
+        "x".repeat(8159),
+    );
+    let outcomes = |stream: StreamDeserializer<Record>| {
+        stream
+            .map(|record| match record {
+                Ok(record) => format!("{}", record.id),
+                Err(err) => format!("{:?} on line {:?}", err.kind(), err.line()),
+            })
+            .collect::<Vec<_>>()
+    };
+    let expected = outcomes(StreamDeserializer::from_str(&j));
+    assert_eq!(
+        expected,
+        [
+            "1",
+            "Invalid on line Some(3)",
+            "Invalid on line Some(3)",
+            "3"
+        ],
+    );
+    assert_eq!(
+        outcomes(StreamDeserializer::from_reader(j.as_bytes())),
+        expected
+    );
+
+    // Lines too long to be kept are carried on with from where the error was
+    // detected.
+    let j = format!("[1,\n\"{}\" 2]\n[3]\n", "x".repeat(20_000));
+    let mut stream = StreamDeserializer::<Value>::from_reader(j.as_bytes());
+    assert_eq!(stream.next().unwrap().unwrap_err().line(), Some(2));
+    let err = stream.next().unwrap().unwrap_err();
+    assert_eq!(err.column(), Some(20004));
+    assert_eq!(json::to_string(&stream.next().unwrap().unwrap()), "[3]");
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn test_limits() {
+    let limits = Limits::new().max_values(3);
+    let j = "[1, 2]\n[1, 2, 3]\n[3]\n";
+    for stream in vec![
+        limits.stream_from_str::<Vec<u32>>(j),
+        limits.stream_from_slice::<Vec<u32>>(j.as_bytes()),
+        limits.stream_from_reader::<_, Vec<u32>>(j.as_bytes()),
+    ] {
+        let results = stream.collect::<Vec<_>>();
+        assert_eq!(results[0].as_ref().ok(), Some(&vec![1, 2]));
+        assert_eq!(
+            results[1].as_ref().unwrap_err().kind(),
+            ErrorKind::TooManyValues
+        );
+        assert_eq!(results[2].as_ref().ok(), Some(&vec![3]));
+    }
+}
+
+#[test]
+fn test_resume_after_panic() {
+    use std::convert::TryFrom;
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    #[serde(try_from = "u32")]
+    struct Port(u32);
+
+    impl TryFrom<u32> for Port {
+        type Error = &'static str;
+
+        fn try_from(port: u32) -> Result<Self, Self::Error> {
+            assert!(port != 0, "port 0");
+            Ok(Port(port))
+        }
+    }
+
+    // The panic leaves the Vec half built. None of it may be around to be
+    // finished by the `null` that follows, which is skipped along with the
+    // rest of its line.
+    let j = "[0 null]\n[1]\n[0]\n";
+    let mut stream = StreamDeserializer::<Option<Vec<Port>>>::from_str(j);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| stream.next()));
+    assert!(result.is_err());
+    assert_eq!(stream.next().unwrap().ok(), Some(Some(vec![Port(1)])));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| stream.next()));
+    assert!(result.is_err());
+    drop(stream);
+}
@@ -2,7 +2,7 @@ use miniserde::Serialize;
 
 #[derive(Serialize)]
 struct Struct {
-    #[serde(skip)]
+    #[serde(getter = "Struct::x")]
     x: i32,
 }
 
@@ -1,8 +0,0 @@
-use miniserde::Deserialize;
-
-#[derive(Deserialize)]
-enum Enum<const T: i32> {
-    Variant,
-}
-
-fn main() {}
@@ -0,0 +1,16 @@
+use miniserde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Struct {
+    x: i32,
+    #[serde(flatten)]
+    rest: Inner,
+}
+
+#[derive(Deserialize)]
+struct Inner {
+    y: i32,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use miniserde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(from = "String", into = "String", rename_all = "camelCase")]
+struct Email {
+    address: String,
+}
+
+impl From<String> for Email {
+    fn from(address: String) -> Self {
+        Email { address }
+    }
+}
+
+impl From<Email> for String {
+    fn from(email: Email) -> Self {
+        email.address
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use miniserde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(from = "String", try_from = "String")]
+struct Email(String);
+
+fn main() {}
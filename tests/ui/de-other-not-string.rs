@@ -2,7 +2,9 @@ use miniserde::Deserialize;
 
 #[derive(Deserialize)]
 enum Enum {
-    Variant(i32)
+    Number(u64),
+    #[serde(other)]
+    Unknown(u64),
 }
 
 fn main() {}
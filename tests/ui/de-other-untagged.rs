@@ -0,0 +1,11 @@
+use miniserde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Enum {
+    Number(u64),
+    #[serde(other)]
+    Unknown,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use miniserde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(try_from = "String", deny_unknown_fields)]
+struct Email {
+    address: String,
+}
+
+fn main() {}
@@ -1,6 +1,7 @@
 use miniserde::Deserialize;
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 struct TupleStruct(i32, i32);
 
 fn main() {}
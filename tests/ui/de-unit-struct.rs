@@ -1,6 +0,0 @@
-use miniserde::Deserialize;
-
-#[derive(Deserialize)]
-struct UnitStruct;
-
-fn main() {}
@@ -1,8 +0,0 @@
-use miniserde::Serialize;
-
-#[derive(Serialize)]
-enum Enum<const T: i32> {
-    Variant,
-}
-
-fn main() {}
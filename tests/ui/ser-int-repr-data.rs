@@ -1,8 +1,10 @@
 use miniserde::Serialize;
 
 #[derive(Serialize)]
+#[serde(repr = "int")]
 enum Enum {
-    Variant(i32)
+    Unit,
+    Newtype(u8),
 }
 
 fn main() {}
@@ -0,0 +1,10 @@
+use miniserde::Serialize;
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum Enum {
+    Unit,
+    Tuple(i32, i32),
+}
+
+fn main() {}
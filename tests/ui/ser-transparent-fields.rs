@@ -0,0 +1,10 @@
+use miniserde::Serialize;
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Range {
+    start: u64,
+    end: u64,
+}
+
+fn main() {}
@@ -1,6 +0,0 @@
-use miniserde::Serialize;
-
-#[derive(Serialize)]
-struct UnitStruct;
-
-fn main() {}